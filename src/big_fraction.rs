@@ -0,0 +1,447 @@
+use std::{
+	cmp::Ordering,
+	fmt::{Debug, Display},
+	ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+	str::FromStr,
+};
+
+use crate::{
+	big_uint::BigUint,
+	fraction::{Decimal, Fraction, ParseFractionError},
+};
+
+/// The largest exponent [`BigFraction::from_str`] accepts, since `10^exponent` is stored exactly.
+const MAX_EXPONENT: u64 = 100_000;
+
+/// An arbitrary-precision fraction, the counterpart of [`Fraction`] which never overflows.
+///
+/// Always kept reduced with a non-zero denominator.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BigFraction {
+	negative: bool,
+	numerator: BigUint,
+	denominator: BigUint,
+}
+impl BigFraction {
+	/// # Panics
+	/// Panics if `denominator` is zero.
+	pub fn new(negative: bool, numerator: BigUint, denominator: BigUint) -> Self {
+		assert!(!denominator.is_zero(), "denominator can't be zero");
+		let gcd = numerator.gcd(&denominator);
+		Self {
+			negative: negative && !numerator.is_zero(),
+			numerator: numerator.div_rem(&gcd).0,
+			denominator: denominator.div_rem(&gcd).0,
+		}
+	}
+
+	pub fn zero() -> Self {
+		Self::from(0)
+	}
+
+	pub fn one() -> Self {
+		Self::from(1)
+	}
+
+	pub fn numerator(&self) -> &BigUint {
+		&self.numerator
+	}
+
+	pub fn denominator(&self) -> &BigUint {
+		&self.denominator
+	}
+
+	pub fn sign(&self) -> isize {
+		if self.numerator.is_zero() {
+			0
+		} else if self.negative {
+			-1
+		} else {
+			1
+		}
+	}
+
+	pub fn is_negative(&self) -> bool {
+		self.negative
+	}
+
+	pub fn abs(&self) -> Self {
+		Self {
+			negative: false,
+			..self.clone()
+		}
+	}
+
+	/// The reciprocal, zero stays zero like [`Fraction::swapped`].
+	pub fn swapped(&self) -> Self {
+		if self.numerator.is_zero() {
+			self.clone()
+		} else {
+			Self {
+				negative: self.negative,
+				numerator: self.denominator.clone(),
+				denominator: self.numerator.clone(),
+			}
+		}
+	}
+
+	pub fn as_f64(&self) -> f64 {
+		self.sign() as f64 * self.numerator.as_f64() / self.denominator.as_f64()
+	}
+
+	/// Converts back to a [`Fraction`] if numerator and denominator fit.
+	pub fn to_fraction(&self) -> Option<Fraction> {
+		Some(Fraction::new(
+			self.negative,
+			self.numerator.to_u64()?.try_into().ok()?,
+			self.denominator.to_u64()?.try_into().ok()?,
+		))
+	}
+}
+impl<F: Into<Fraction>> From<F> for BigFraction {
+	fn from(f: F) -> Self {
+		let f = f.into();
+		Self::new(
+			f.is_negative(),
+			f.numerator().into(),
+			f.denominator().into(),
+		)
+	}
+}
+impl TryFrom<BigFraction> for Fraction {
+	type Error = ParseFractionError;
+
+	/// Fails with [`ParseFractionError::Overflow`] if numerator or denominator don't fit.
+	fn try_from(f: BigFraction) -> Result<Self, Self::Error> {
+		f.to_fraction().ok_or(ParseFractionError::Overflow)
+	}
+}
+impl From<BigFraction> for f64 {
+	fn from(f: BigFraction) -> Self {
+		f.as_f64()
+	}
+}
+/// Parses the same numbers as [`Fraction::from_str`], but without their size limit.
+impl FromStr for BigFraction {
+	type Err = ParseFractionError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.split_once('/') {
+			Some((n, d)) => {
+				let (n, d) = (parse_decimal(n)?, parse_decimal(d)?);
+				if d.sign() == 0 {
+					Err(ParseFractionError::ZeroDenominator)
+				} else {
+					Ok(&n / &d)
+				}
+			}
+			None => parse_decimal(s),
+		}
+	}
+}
+fn parse_decimal(s: &str) -> Result<BigFraction, ParseFractionError> {
+	let Decimal {
+		negative,
+		digits,
+		exponent,
+	} = Decimal::parse(s)?;
+	if digits.is_empty() {
+		return Ok(BigFraction::zero());
+	}
+	let numerator = BigUint::from_decimal(&digits).expect("`Decimal::parse` only keeps digits");
+	let exponent = exponent
+		.filter(|e| e.unsigned_abs() <= MAX_EXPONENT)
+		.ok_or(ParseFractionError::Overflow)?;
+	let power = BigUint::from(10u32).pow(exponent.unsigned_abs());
+	Ok(if exponent >= 0 {
+		BigFraction::new(negative, &numerator * &power, BigUint::one())
+	} else {
+		BigFraction::new(negative, numerator, power)
+	})
+}
+impl Add<&BigFraction> for &BigFraction {
+	type Output = BigFraction;
+
+	fn add(self, rhs: &BigFraction) -> Self::Output {
+		let a = &self.numerator * &rhs.denominator;
+		let b = &rhs.numerator * &self.denominator;
+		let (negative, numerator) = if self.negative == rhs.negative {
+			(self.negative, &a + &b)
+		} else if a >= b {
+			(self.negative, &a - &b)
+		} else {
+			(rhs.negative, &b - &a)
+		};
+		BigFraction::new(negative, numerator, &self.denominator * &rhs.denominator)
+	}
+}
+impl Add<BigFraction> for BigFraction {
+	type Output = BigFraction;
+
+	fn add(self, rhs: BigFraction) -> Self::Output {
+		&self + &rhs
+	}
+}
+impl AddAssign<BigFraction> for BigFraction {
+	fn add_assign(&mut self, rhs: BigFraction) {
+		*self = &*self + &rhs;
+	}
+}
+impl Sub<&BigFraction> for &BigFraction {
+	type Output = BigFraction;
+
+	fn sub(self, rhs: &BigFraction) -> Self::Output {
+		self + &-rhs.clone()
+	}
+}
+impl Sub<BigFraction> for BigFraction {
+	type Output = BigFraction;
+
+	fn sub(self, rhs: BigFraction) -> Self::Output {
+		self + -rhs
+	}
+}
+impl SubAssign<BigFraction> for BigFraction {
+	fn sub_assign(&mut self, rhs: BigFraction) {
+		*self += -rhs;
+	}
+}
+impl Mul<&BigFraction> for &BigFraction {
+	type Output = BigFraction;
+
+	fn mul(self, rhs: &BigFraction) -> Self::Output {
+		BigFraction::new(
+			self.negative ^ rhs.negative,
+			&self.numerator * &rhs.numerator,
+			&self.denominator * &rhs.denominator,
+		)
+	}
+}
+impl Mul<BigFraction> for BigFraction {
+	type Output = BigFraction;
+
+	fn mul(self, rhs: BigFraction) -> Self::Output {
+		&self * &rhs
+	}
+}
+impl MulAssign<BigFraction> for BigFraction {
+	fn mul_assign(&mut self, rhs: BigFraction) {
+		*self = &*self * &rhs;
+	}
+}
+impl Div<&BigFraction> for &BigFraction {
+	type Output = BigFraction;
+
+	/// # Panics
+	/// Panics if `rhs` is zero.
+	#[allow(clippy::suspicious_arithmetic_impl)]
+	fn div(self, rhs: &BigFraction) -> Self::Output {
+		assert!(rhs.sign() != 0, "attempt to divide by zero");
+		self * &rhs.swapped()
+	}
+}
+impl Div<BigFraction> for BigFraction {
+	type Output = BigFraction;
+
+	fn div(self, rhs: BigFraction) -> Self::Output {
+		&self / &rhs
+	}
+}
+impl DivAssign<BigFraction> for BigFraction {
+	fn div_assign(&mut self, rhs: BigFraction) {
+		*self = &*self / &rhs;
+	}
+}
+impl Neg for BigFraction {
+	type Output = BigFraction;
+
+	fn neg(self) -> Self::Output {
+		Self {
+			negative: !self.negative && !self.numerator.is_zero(),
+			..self
+		}
+	}
+}
+impl PartialOrd for BigFraction {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+impl Ord for BigFraction {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.sign().cmp(&other.sign()).then_with(|| {
+			let magnitude =
+				(&self.numerator * &other.denominator).cmp(&(&other.numerator * &self.denominator));
+			if self.negative {
+				magnitude.reverse()
+			} else {
+				magnitude
+			}
+		})
+	}
+}
+impl Display for BigFraction {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.pad(&format!(
+			"{}{}{}",
+			if self.negative {
+				"-"
+			} else if f.sign_plus() {
+				"+"
+			} else {
+				""
+			},
+			self.numerator,
+			if self.denominator.is_one() {
+				String::new()
+			} else {
+				format!("/{}", self.denominator)
+			}
+		))
+	}
+}
+impl Debug for BigFraction {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}", self)
+	}
+}
+/// Written and read like [`Fraction`], but strings aren't limited in size.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BigFraction {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BigFraction {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(BigFractionVisitor)
+	}
+}
+#[cfg(feature = "serde")]
+struct BigFractionVisitor;
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for BigFractionVisitor {
+	type Value = BigFraction;
+
+	fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		crate::fraction::FractionVisitor.expecting(f)
+	}
+
+	fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<BigFraction, E> {
+		v.parse().map_err(E::custom)
+	}
+
+	fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<BigFraction, E> {
+		Ok(BigFraction::from(v))
+	}
+
+	fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<BigFraction, E> {
+		Ok(BigFraction::from(v))
+	}
+
+	fn visit_map<A: serde::de::MapAccess<'de>>(self, map: A) -> Result<BigFraction, A::Error> {
+		crate::fraction::FractionVisitor
+			.visit_map(map)
+			.map(BigFraction::from)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		big_fraction::BigFraction,
+		fraction::{Fraction, ParseFractionError},
+	};
+
+	type B = BigFraction;
+
+	#[test]
+	fn from_fraction() {
+		assert_eq!(B::from((6, 8)).to_string(), "3/4");
+		assert_eq!(B::from(-3).to_string(), "-3");
+		assert_eq!(B::from(0).to_string(), "0");
+		assert_eq!(
+			B::from((-1, 2)).to_fraction(),
+			Some(Fraction::negative(1, 2))
+		);
+	}
+
+	#[test]
+	fn arithmetic() {
+		assert_eq!(B::from((1, 2)) + B::from((1, 3)), B::from((5, 6)));
+		assert_eq!(B::from((1, 2)) - B::from((1, 2)), B::zero());
+		assert_eq!(B::from((1, 3)) - B::from((1, 2)), B::from((-1, 6)));
+		assert_eq!(B::from((3, 2)) * B::from((-1, 3)), B::from((-1, 2)));
+		assert_eq!(B::from((3, 2)) / B::from(3), B::from((1, 2)));
+		assert_eq!(-B::zero(), B::zero());
+	}
+
+	#[test]
+	#[should_panic(expected = "attempt to divide by zero")]
+	fn div_by_zero_panics() {
+		let _ = B::one() / B::zero();
+	}
+
+	#[test]
+	fn no_overflow() {
+		let mut cut = B::from(usize::MAX);
+		cut *= B::from(usize::MAX);
+		cut += B::one();
+		assert_eq!(cut.to_string(), "340282366920938463426481119284349108226");
+		assert_eq!(cut.to_fraction(), None);
+		cut /= B::from(usize::MAX);
+		cut -= B::from(usize::MAX);
+		assert_eq!(cut, B::from((1, usize::MAX)));
+	}
+
+	#[test]
+	fn ordering() {
+		assert!(B::from((1, 3)) < B::from((1, 2)));
+		assert!(B::from((-1, 2)) < B::from((-1, 3)));
+		assert!(B::from(-1) < B::zero());
+	}
+
+	#[test]
+	fn from_str() {
+		let parse = |s: &str| s.parse::<B>();
+		assert_eq!(parse("-3.5e-2"), Ok(B::from((-7, 200))));
+		assert_eq!(parse(".5/-0.25"), Ok(B::from(-2)));
+		assert_eq!(parse("-0.0e-99999999999999999999"), Ok(B::zero()));
+		let huge = parse("123456789012345678901234567890/1e30").unwrap();
+		assert_eq!(
+			huge.to_string(),
+			"12345678901234567890123456789/100000000000000000000000000000"
+		);
+		assert_eq!(Fraction::try_from(huge), Err(ParseFractionError::Overflow));
+		assert_eq!(
+			Fraction::try_from(B::from((3, 4))),
+			Ok(Fraction::positive(3, 4))
+		);
+		assert_eq!(f64::from(B::from((3, 4))), 0.75);
+		assert_eq!(parse("1/0.0"), Err(ParseFractionError::ZeroDenominator));
+		assert_eq!(parse("1e99999999999"), Err(ParseFractionError::Overflow));
+		for invalid in ["", ".", "1e", "1_", "1.2.3", "0x10", "--1"] {
+			assert_eq!(
+				parse(invalid),
+				Err(ParseFractionError::Invalid),
+				"{invalid}"
+			);
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn serde() {
+		let huge = r#""123456789012345678901234567891/2""#;
+		let parsed = serde_json::from_str::<B>(huge).unwrap();
+		assert_eq!(parsed, "123456789012345678901234567891/2".parse().unwrap());
+		assert_eq!(serde_json::to_string(&parsed).unwrap(), huge);
+		assert_eq!(serde_json::from_str::<B>("-3").unwrap(), B::from(-3));
+		assert_eq!(
+			serde_json::from_str::<B>(r#"{"num": 1, "den": -2}"#).unwrap(),
+			B::from((-1, 2))
+		);
+		assert!(serde_json::from_str::<B>(r#""1/0""#).is_err());
+	}
+}
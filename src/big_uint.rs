@@ -0,0 +1,377 @@
+use std::{
+	cmp::Ordering,
+	fmt::{Debug, Display},
+	ops::{Add, Mul, Sub},
+};
+
+const BASE: u64 = 1 << 32;
+
+/// An arbitrary-precision unsigned integer.
+///
+/// Stored as base 2^32 limbs, least significant first, without leading zero limbs.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+	limbs: Vec<u32>,
+}
+impl BigUint {
+	pub const ZERO: Self = Self { limbs: Vec::new() };
+
+	fn from_limbs(mut limbs: Vec<u32>) -> Self {
+		while limbs.last() == Some(&0) {
+			limbs.pop();
+		}
+		Self { limbs }
+	}
+
+	pub fn one() -> Self {
+		Self::from(1u32)
+	}
+
+	pub fn is_zero(&self) -> bool {
+		self.limbs.is_empty()
+	}
+
+	pub fn is_one(&self) -> bool {
+		self.limbs == [1]
+	}
+
+	/// Returns the value if it fits into an `u64`.
+	pub fn to_u64(&self) -> Option<u64> {
+		match self.limbs[..] {
+			[] => Some(0),
+			[a] => Some(a as u64),
+			[a, b] => Some(a as u64 | (b as u64) << 32),
+			_ => None,
+		}
+	}
+
+	pub fn as_f64(&self) -> f64 {
+		self.limbs
+			.iter()
+			.rev()
+			.fold(0.0, |acc, l| acc * BASE as f64 + *l as f64)
+	}
+
+	/// Subtracts `rhs`, returning `None` if `rhs` is greater than `self`.
+	pub fn checked_sub(&self, rhs: &BigUint) -> Option<BigUint> {
+		if *self < *rhs {
+			return None;
+		}
+		let mut limbs = Vec::with_capacity(self.limbs.len());
+		let mut borrow = 0;
+		for (i, l) in self.limbs.iter().enumerate() {
+			let diff = *l as i64 - *rhs.limbs.get(i).unwrap_or(&0) as i64 - borrow;
+			limbs.push(diff as u32);
+			borrow = (diff < 0) as i64;
+		}
+		Some(Self::from_limbs(limbs))
+	}
+
+	/// Returns the quotient and the remainder of `self / rhs`.
+	///
+	/// # Panics
+	/// Panics if `rhs` is zero.
+	pub fn div_rem(&self, rhs: &BigUint) -> (BigUint, BigUint) {
+		assert!(!rhs.is_zero(), "attempt to divide by zero");
+		if self < rhs {
+			return (Self::ZERO, self.clone());
+		}
+		if let [d] = rhs.limbs[..] {
+			let (q, r) = self.div_rem_limb(d);
+			return (q, Self::from(r));
+		}
+		self.div_rem_knuth(rhs)
+	}
+
+	fn div_rem_limb(&self, d: u32) -> (BigUint, u32) {
+		let mut q = vec![0; self.limbs.len()];
+		let mut r = 0u64;
+		for (i, l) in self.limbs.iter().enumerate().rev() {
+			let cur = r << 32 | *l as u64;
+			q[i] = (cur / d as u64) as u32;
+			r = cur % d as u64;
+		}
+		(Self::from_limbs(q), r as u32)
+	}
+
+	/// Knuth's algorithm D, `rhs` must have at least two limbs.
+	fn div_rem_knuth(&self, rhs: &BigUint) -> (BigUint, BigUint) {
+		let n = rhs.limbs.len();
+		let m = self.limbs.len();
+		let shift = rhs.limbs[n - 1].leading_zeros();
+		let v = shl_limbs(&rhs.limbs, shift);
+		let mut u = shl_limbs(&self.limbs, shift);
+		u.resize(m + 1, 0);
+		let mut q = vec![0; m - n + 1];
+		for j in (0..=m - n).rev() {
+			let num = (u[j + n] as u64) << 32 | u[j + n - 1] as u64;
+			let mut qhat = num / v[n - 1] as u64;
+			let mut rhat = num % v[n - 1] as u64;
+			while qhat >= BASE || qhat * v[n - 2] as u64 > (rhat << 32 | u[j + n - 2] as u64) {
+				qhat -= 1;
+				rhat += v[n - 1] as u64;
+				if rhat >= BASE {
+					break;
+				}
+			}
+			// multiply and subtract
+			let mut borrow = 0i64;
+			let mut carry = 0u64;
+			for i in 0..n {
+				let p = qhat * v[i] as u64 + carry;
+				carry = p >> 32;
+				let t = u[i + j] as i64 - borrow - (p & (BASE - 1)) as i64;
+				u[i + j] = t as u32;
+				borrow = (t < 0) as i64;
+			}
+			let t = u[j + n] as i64 - borrow - carry as i64;
+			u[j + n] = t as u32;
+			if t < 0 {
+				// subtracted one time too often, add back
+				qhat -= 1;
+				let mut carry = 0u64;
+				for i in 0..n {
+					let s = u[i + j] as u64 + v[i] as u64 + carry;
+					u[i + j] = s as u32;
+					carry = s >> 32;
+				}
+				u[j + n] = u[j + n].wrapping_add(carry as u32);
+			}
+			q[j] = qhat as u32;
+		}
+		u.truncate(n);
+		(Self::from_limbs(q), Self::from_limbs(shr_limbs(&u, shift)))
+	}
+
+	/// `self^exp` by repeated squaring.
+	pub fn pow(&self, mut exp: u64) -> BigUint {
+		let mut base = self.clone();
+		let mut result = Self::one();
+		while exp > 0 {
+			if exp % 2 == 1 {
+				result = &result * &base;
+			}
+			exp /= 2;
+			if exp > 0 {
+				base = &base * &base;
+			}
+		}
+		result
+	}
+
+	/// Parses decimal digits, `None` if `digits` is empty or contains anything else.
+	pub fn from_decimal(digits: &str) -> Option<BigUint> {
+		if digits.is_empty() || !digits.bytes().all(|d| d.is_ascii_digit()) {
+			return None;
+		}
+		// nine digits always fit into a limb
+		Some(digits.as_bytes().chunks(9).fold(Self::ZERO, |n, chunk| {
+			let chunk_value = chunk.iter().fold(0, |v, d| v * 10 + (d - b'0') as u32);
+			&(&n * &Self::from(10u32.pow(chunk.len() as u32))) + &Self::from(chunk_value)
+		}))
+	}
+
+	pub fn gcd(&self, other: &BigUint) -> BigUint {
+		let mut a = self.clone();
+		let mut b = other.clone();
+		while !b.is_zero() {
+			let r = a.div_rem(&b).1;
+			a = b;
+			b = r;
+		}
+		a
+	}
+}
+fn shl_limbs(limbs: &[u32], shift: u32) -> Vec<u32> {
+	if shift == 0 {
+		return limbs.to_vec();
+	}
+	let mut new = Vec::with_capacity(limbs.len() + 1);
+	let mut carry = 0;
+	for l in limbs {
+		new.push(l << shift | carry);
+		carry = l >> (32 - shift);
+	}
+	if carry != 0 {
+		new.push(carry);
+	}
+	new
+}
+fn shr_limbs(limbs: &[u32], shift: u32) -> Vec<u32> {
+	if shift == 0 {
+		return limbs.to_vec();
+	}
+	(0..limbs.len())
+		.map(|i| limbs[i] >> shift | limbs.get(i + 1).map_or(0, |l| l << (32 - shift)))
+		.collect()
+}
+impl From<u64> for BigUint {
+	fn from(n: u64) -> Self {
+		Self::from_limbs(vec![n as u32, (n >> 32) as u32])
+	}
+}
+impl From<u32> for BigUint {
+	fn from(n: u32) -> Self {
+		Self::from(n as u64)
+	}
+}
+impl From<usize> for BigUint {
+	fn from(n: usize) -> Self {
+		Self::from(n as u64)
+	}
+}
+impl Add<&BigUint> for &BigUint {
+	type Output = BigUint;
+
+	fn add(self, rhs: &BigUint) -> Self::Output {
+		let len = self.limbs.len().max(rhs.limbs.len());
+		let mut limbs = Vec::with_capacity(len + 1);
+		let mut carry = 0u64;
+		for i in 0..len {
+			let s = *self.limbs.get(i).unwrap_or(&0) as u64
+				+ *rhs.limbs.get(i).unwrap_or(&0) as u64
+				+ carry;
+			limbs.push(s as u32);
+			carry = s >> 32;
+		}
+		limbs.push(carry as u32);
+		BigUint::from_limbs(limbs)
+	}
+}
+impl Sub<&BigUint> for &BigUint {
+	type Output = BigUint;
+
+	fn sub(self, rhs: &BigUint) -> Self::Output {
+		self.checked_sub(rhs)
+			.expect("attempt to subtract with overflow")
+	}
+}
+impl Mul<&BigUint> for &BigUint {
+	type Output = BigUint;
+
+	fn mul(self, rhs: &BigUint) -> Self::Output {
+		if self.is_zero() || rhs.is_zero() {
+			return BigUint::ZERO;
+		}
+		let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+		for (i, a) in self.limbs.iter().enumerate() {
+			let mut carry = 0u64;
+			for (j, b) in rhs.limbs.iter().enumerate() {
+				let p = *a as u64 * *b as u64 + limbs[i + j] as u64 + carry;
+				limbs[i + j] = p as u32;
+				carry = p >> 32;
+			}
+			limbs[i + rhs.limbs.len()] = carry as u32;
+		}
+		BigUint::from_limbs(limbs)
+	}
+}
+impl PartialOrd for BigUint {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+impl Ord for BigUint {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.limbs
+			.len()
+			.cmp(&other.limbs.len())
+			.then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+	}
+}
+impl Display for BigUint {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		if self.is_zero() {
+			return f.pad("0");
+		}
+		// split into base 10^9 chunks, least significant first
+		let mut chunks = Vec::new();
+		let mut rest = self.clone();
+		while !rest.is_zero() {
+			let (q, r) = rest.div_rem_limb(1_000_000_000);
+			chunks.push(r);
+			rest = q;
+		}
+		let mut s = chunks.pop().unwrap().to_string();
+		chunks
+			.iter()
+			.rev()
+			.for_each(|c| s.push_str(&format!("{c:09}")));
+		f.pad(&s)
+	}
+}
+impl Debug for BigUint {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}", self)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::BigUint;
+
+	fn pow(base: u64, exp: u32) -> BigUint {
+		(0..exp).fold(BigUint::one(), |acc, _| &acc * &BigUint::from(base))
+	}
+
+	#[test]
+	fn display() {
+		assert_eq!(BigUint::ZERO.to_string(), "0");
+		assert_eq!(BigUint::from(u64::MAX).to_string(), "18446744073709551615");
+		assert_eq!(pow(10, 30).to_string(), format!("1{}", "0".repeat(30)));
+	}
+
+	#[test]
+	fn add_sub() {
+		let a = BigUint::from(u64::MAX);
+		let b = &a + &BigUint::one();
+		assert_eq!(b.to_string(), "18446744073709551616");
+		assert_eq!(&b - &BigUint::one(), a);
+		assert_eq!(a.checked_sub(&b), None);
+		assert_eq!(&b - &b, BigUint::ZERO);
+	}
+
+	#[test]
+	fn pow_and_decimal() {
+		assert_eq!(BigUint::from(10u32).pow(30), pow(10, 30));
+		assert_eq!(BigUint::from(7u32).pow(0), BigUint::one());
+		let digits = "340282366920938463426481119284349108225";
+		assert_eq!(BigUint::from_decimal(digits).unwrap().to_string(), digits);
+		assert_eq!(BigUint::from_decimal("007"), Some(BigUint::from(7u32)));
+		assert_eq!(BigUint::from_decimal(""), None);
+		assert_eq!(BigUint::from_decimal("1_0"), None);
+	}
+
+	#[test]
+	fn mul() {
+		assert_eq!(
+			(&BigUint::from(u64::MAX) * &BigUint::from(u64::MAX)).to_string(),
+			"340282366920938463426481119284349108225"
+		);
+	}
+
+	#[test]
+	fn div_rem() {
+		let a = &pow(7, 60) + &BigUint::from(12345u32);
+		let b = pow(7, 25);
+		let (q, r) = a.div_rem(&b);
+		assert_eq!(q, pow(7, 35));
+		assert_eq!(r, BigUint::from(12345u32));
+
+		let (q, r) = pow(3, 100).div_rem(&BigUint::from(10u32));
+		assert_eq!(&(&q * &BigUint::from(10u32)) + &r, pow(3, 100));
+		assert_eq!(r, BigUint::from(1u32));
+
+		// a divisor whose top limb needs the most normalization
+		let b = &pow(2, 64) + &BigUint::one();
+		let a = &(&b * &pow(5, 40)) + &pow(2, 63);
+		assert_eq!(a.div_rem(&b), (pow(5, 40), pow(2, 63)));
+	}
+
+	#[test]
+	fn gcd() {
+		let a = &pow(6, 40) * &pow(35, 3);
+		let b = &pow(10, 50) * &pow(7, 2);
+		assert_eq!(a.gcd(&b), &(&pow(2, 40) * &pow(5, 3)) * &pow(7, 2));
+	}
+}
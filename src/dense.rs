@@ -0,0 +1,312 @@
+use std::{
+	fmt::Display,
+	ops::{Add, Index, Mul, Sub},
+};
+
+use crate::{fraction::Fraction, matrix::Matrix, row::Row, scalar::Scalar};
+
+/// A plain `m × n` matrix for arithmetic, e.g. checking `A·x = b`.
+///
+/// Every row is a [`Row`] without right side. The arithmetic operators
+/// of the cells are used, so this panics if a cell overflows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseMatrix<T = Fraction> {
+	rows: Vec<Row<T>>,
+	width: usize,
+}
+impl<T: Scalar> DenseMatrix<T> {
+	/// Panics if the rows don't have the same length.
+	/// The width is the one of the first row, see [`DenseMatrix::with_width`] for no rows.
+	pub fn new(rows: Vec<Vec<T>>) -> Self {
+		let width = rows.first().map_or(0, Vec::len);
+		Self::with_width(rows, width)
+	}
+
+	/// Like [`DenseMatrix::new`], but keeps `width` even if there are no rows.
+	///
+	/// Panics if a row doesn't have `width` elements.
+	pub fn with_width(rows: Vec<Vec<T>>, width: usize) -> Self {
+		if let Some(i) = rows.iter().position(|r| r.len() != width) {
+			panic!("Row {} needs {} elements.", i + 1, width);
+		}
+		Self {
+			rows: rows.into_iter().map(|r| Row::new(r, Vec::new())).collect(),
+			width,
+		}
+	}
+
+	pub fn zeros(height: usize, width: usize) -> Self {
+		Self::with_width(vec![vec![T::zero(); width]; height], width)
+	}
+
+	pub fn identity(n: usize) -> Self {
+		let mut identity = Self::zeros(n, n);
+		for (i, r) in identity.rows.iter_mut().enumerate() {
+			r[i as isize] = T::one();
+		}
+		identity
+	}
+
+	pub fn height(&self) -> usize {
+		self.rows.len()
+	}
+
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	pub fn row(&self, index: usize) -> &Vec<T> {
+		self.rows[index].left()
+	}
+
+	pub fn transpose(&self) -> Self {
+		Self::with_width(
+			(0..self.width)
+				.map(|c| self.rows.iter().map(|r| r.left()[c].clone()).collect())
+				.collect(),
+			self.height(),
+		)
+	}
+
+	/// Multiplies the square matrix `exponent` times with itself, `pow(0)` is the identity.
+	pub fn pow(&self, mut exponent: u32) -> Self {
+		assert_eq!(
+			self.height(),
+			self.width,
+			"Only square matrices have powers."
+		);
+		let mut result = Self::identity(self.width);
+		let mut base = self.clone();
+		while exponent > 0 {
+			if exponent % 2 == 1 {
+				result = &result * &base;
+			}
+			exponent /= 2;
+			// squaring after the last bit could overflow even though it isn't used
+			if exponent > 0 {
+				base = &base * &base;
+			}
+		}
+		result
+	}
+
+	/// Like multiplying with the column vector `rhs`, but `None` if a cell overflows.
+	pub fn checked_mul_vector(&self, rhs: &[T]) -> Option<Vec<T>> {
+		assert_eq!(self.width, rhs.len(), "The vector needs one cell per col.");
+		self.rows
+			.iter()
+			.map(|r| {
+				r.left().iter().zip(rhs).try_fold(T::zero(), |sum, (a, b)| {
+					a.checked_mul(b).and_then(|p| sum.checked_add(&p))
+				})
+			})
+			.collect()
+	}
+
+	fn assert_same_size(&self, rhs: &Self) {
+		assert_eq!(
+			(self.height(), self.width),
+			(rhs.height(), rhs.width),
+			"The matrices need the same size."
+		);
+	}
+}
+/// The left side of `m`.
+impl<T: Scalar> From<&Matrix<T>> for DenseMatrix<T> {
+	fn from(m: &Matrix<T>) -> Self {
+		Self::new(m.rows().iter().map(|r| r.left().clone()).collect())
+	}
+}
+impl<T: Scalar> Index<(usize, usize)> for DenseMatrix<T> {
+	type Output = T;
+
+	fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+		&self.rows[row].left()[col]
+	}
+}
+impl<T: Scalar> Add for DenseMatrix<T> {
+	type Output = DenseMatrix<T>;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		self.assert_same_size(&rhs);
+		Self {
+			rows: self
+				.rows
+				.into_iter()
+				.zip(rhs.rows)
+				.map(|(a, b)| a + b)
+				.collect(),
+			width: self.width,
+		}
+	}
+}
+impl<T: Scalar> Sub for DenseMatrix<T> {
+	type Output = DenseMatrix<T>;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		self.assert_same_size(&rhs);
+		Self {
+			rows: self
+				.rows
+				.into_iter()
+				.zip(rhs.rows)
+				.map(|(a, b)| a - b)
+				.collect(),
+			width: self.width,
+		}
+	}
+}
+/// Multiplies every cell with a scalar.
+impl<T: Scalar> Mul<T> for DenseMatrix<T> {
+	type Output = DenseMatrix<T>;
+
+	fn mul(self, rhs: T) -> Self::Output {
+		Self {
+			rows: self.rows.into_iter().map(|r| r * rhs.clone()).collect(),
+			width: self.width,
+		}
+	}
+}
+impl<T: Scalar> Mul for &DenseMatrix<T> {
+	type Output = DenseMatrix<T>;
+
+	fn mul(self, rhs: Self) -> Self::Output {
+		assert_eq!(
+			self.width,
+			rhs.height(),
+			"The left matrix needs as many cols as the right one has rows."
+		);
+		DenseMatrix::with_width(
+			self.rows
+				.iter()
+				.map(|r| {
+					(0..rhs.width)
+						.map(|c| dot(r.left(), rhs.rows.iter().map(|o| &o.left()[c])))
+						.collect()
+				})
+				.collect(),
+			rhs.width,
+		)
+	}
+}
+impl<T: Scalar> Mul for DenseMatrix<T> {
+	type Output = DenseMatrix<T>;
+
+	fn mul(self, rhs: Self) -> Self::Output {
+		&self * &rhs
+	}
+}
+/// Multiplies with a column vector.
+impl<T: Scalar> Mul<&[T]> for &DenseMatrix<T> {
+	type Output = Vec<T>;
+
+	fn mul(self, rhs: &[T]) -> Self::Output {
+		assert_eq!(self.width, rhs.len(), "The vector needs one cell per col.");
+		self.rows.iter().map(|r| dot(r.left(), rhs)).collect()
+	}
+}
+fn dot<'a, T: Scalar + 'a>(a: &[T], b: impl IntoIterator<Item = &'a T>) -> T {
+	a.iter()
+		.zip(b)
+		.fold(T::zero(), |sum, (a, b)| sum + a.clone() * b.clone())
+}
+impl<T: Scalar> Display for DenseMatrix<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		let longest = self
+			.rows
+			.iter()
+			.flat_map(|r| r.left().iter().map(|f| f.to_string().len()))
+			.max()
+			.unwrap_or(0);
+		f.pad(
+			&self
+				.rows
+				.iter()
+				.map(|r| {
+					format!(
+						"({})",
+						r.left()
+							.iter()
+							.map(|f| format!("{f:>longest$}"))
+							.collect::<Vec<String>>()
+							.join(" ")
+					)
+				})
+				.collect::<Vec<String>>()
+				.join("\n"),
+		)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{dense::DenseMatrix, fraction::Fraction, matrix};
+
+	fn dense(rows: &[&[i64]]) -> DenseMatrix {
+		DenseMatrix::new(
+			rows.iter()
+				.map(|r| r.iter().map(|i| Fraction::from(*i)).collect())
+				.collect(),
+		)
+	}
+
+	#[test]
+	fn arithmetic() {
+		let a = dense(&[&[1, 2], &[3, 4]]);
+		let b = dense(&[&[0, 1], &[1, 0]]);
+		assert_eq!(a.clone() + b.clone(), dense(&[&[1, 3], &[4, 4]]));
+		assert_eq!(a.clone() - b.clone(), dense(&[&[1, 1], &[2, 4]]));
+		assert_eq!(a.clone() * b.clone(), dense(&[&[2, 1], &[4, 3]]));
+		assert_eq!(b.clone() * a.clone(), dense(&[&[3, 4], &[1, 2]]));
+		assert_eq!(
+			a.clone() * Fraction::positive(1, 2),
+			DenseMatrix::new(vec![
+				vec![Fraction::positive(1, 2), Fraction::ONE],
+				vec![Fraction::positive(3, 2), Fraction::from(2)]
+			])
+		);
+		assert_eq!(
+			&a * [Fraction::ONE, Fraction::M_ONE].as_slice(),
+			vec![Fraction::from(-1), Fraction::from(-1)]
+		);
+		assert_eq!(
+			a.checked_mul_vector(&[Fraction::ONE, Fraction::M_ONE]),
+			Some(vec![Fraction::from(-1), Fraction::from(-1)])
+		);
+		assert_eq!(
+			a.checked_mul_vector(&[Fraction::from(i64::MAX), Fraction::ZERO]),
+			None
+		);
+		assert_eq!(a.transpose(), dense(&[&[1, 3], &[2, 4]]));
+		assert_eq!(dense(&[&[1, 2, 3]]).transpose(), dense(&[&[1], &[2], &[3]]));
+		assert_eq!(a.pow(0), DenseMatrix::identity(2));
+		assert_eq!(a.pow(3), dense(&[&[37, 54], &[81, 118]]));
+		let large = dense(&[&[1 << 40]]);
+		assert_eq!(large.pow(1), large);
+		assert_eq!(DenseMatrix::<Fraction>::zeros(1, 2), dense(&[&[0, 0]]));
+		let empty = DenseMatrix::<Fraction>::zeros(0, 3);
+		assert_eq!(empty.width(), 3);
+		assert_eq!(empty.transpose(), DenseMatrix::zeros(3, 0));
+		assert_eq!(empty.transpose().transpose(), empty);
+		assert_eq!(&empty * &DenseMatrix::zeros(3, 2), DenseMatrix::zeros(0, 2));
+		assert_eq!(
+			&DenseMatrix::zeros(2, 0) * &DenseMatrix::zeros(0, 3),
+			DenseMatrix::<Fraction>::zeros(2, 3)
+		);
+		assert_eq!(a[(1, 0)], Fraction::from(3));
+		assert_eq!(a.to_string(), "(1 2)\n(3 4)");
+	}
+
+	#[test]
+	fn inverse() {
+		let m = matrix![[0; 2; 1] | [1], [1; 1; 0] | [1], [3; 0; 1] | [1]];
+		let inverse = DenseMatrix::from(&m.inverse().unwrap());
+		assert_eq!(DenseMatrix::from(&m) * inverse, DenseMatrix::identity(3));
+	}
+
+	#[test]
+	#[should_panic]
+	fn wrong_size() {
+		let _ = dense(&[&[1, 2]]) * dense(&[&[1, 2]]);
+	}
+}
@@ -1,7 +1,10 @@
 use std::{
 	cmp::Ordering,
+	error::Error,
 	fmt::{Debug, Display},
+	num::IntErrorKind,
 	ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+	str::FromStr,
 };
 
 const fn gcd(mut a: usize, mut b: usize) -> usize {
@@ -40,7 +43,7 @@ impl Fraction {
 		let gcd = gcd(numerator, denominator);
 		Self {
 			denominator: denominator / gcd,
-			negative: negative ^ (numerator > 0) ^ (denominator > 0),
+			negative: negative && numerator != 0,
 			numerator: numerator / gcd,
 		}
 	}
@@ -109,6 +112,61 @@ impl Fraction {
 	pub fn is_negative(&self) -> bool {
 		self.negative
 	}
+
+	pub fn numerator(&self) -> usize {
+		self.numerator
+	}
+
+	pub fn denominator(&self) -> usize {
+		self.denominator
+	}
+
+	/// Adds two fractions, returning `None` if the numerator or denominator overflows.
+	pub fn checked_add(self, rhs: Fraction) -> Option<Fraction> {
+		let gcd = gcd(self.denominator, rhs.denominator);
+		let denominator = (self.denominator / gcd).checked_mul(rhs.denominator)?;
+		let lhs_numerator = self.numerator.checked_mul(rhs.denominator / gcd)?;
+		let rhs_numerator = rhs.numerator.checked_mul(self.denominator / gcd)?;
+		let (negative, numerator) = if self.negative == rhs.negative {
+			(self.negative, lhs_numerator.checked_add(rhs_numerator)?)
+		} else if lhs_numerator >= rhs_numerator {
+			(self.negative, lhs_numerator - rhs_numerator)
+		} else {
+			(rhs.negative, rhs_numerator - lhs_numerator)
+		};
+		Some(Self::new(negative, numerator, denominator))
+	}
+
+	/// Subtracts two fractions, returning `None` if the numerator or denominator overflows.
+	pub fn checked_sub(self, rhs: Fraction) -> Option<Fraction> {
+		self.checked_add(-rhs)
+	}
+
+	/// Multiplies two fractions, returning `None` if the numerator or denominator overflows.
+	///
+	/// Both sides are cross-reduced first, so the result only overflows if the reduced
+	/// fraction doesn't fit.
+	pub fn checked_mul(self, rhs: Fraction) -> Option<Fraction> {
+		if self.sign() == 0 || rhs.sign() == 0 {
+			return Some(Self::ZERO);
+		}
+		let gcd_a = gcd(self.numerator, rhs.denominator);
+		let gcd_b = gcd(rhs.numerator, self.denominator);
+		Some(Self::new(
+			self.negative ^ rhs.negative,
+			(self.numerator / gcd_a).checked_mul(rhs.numerator / gcd_b)?,
+			(self.denominator / gcd_b).checked_mul(rhs.denominator / gcd_a)?,
+		))
+	}
+
+	/// Divides two fractions, returning `None` on overflow or division by zero.
+	pub fn checked_div(self, rhs: Fraction) -> Option<Fraction> {
+		if rhs.sign() == 0 {
+			None
+		} else {
+			self.checked_mul(rhs.swapped())
+		}
+	}
 }
 impl<N: Into<Fraction>, D: Into<Fraction>> From<(N, D)> for Fraction {
 	fn from(f: (N, D)) -> Self {
@@ -120,7 +178,7 @@ impl From<isize> for Fraction {
 		match n.partial_cmp(&0) {
 			Some(Ordering::Greater) => Self::positive_n(n as usize),
 			Some(Ordering::Equal) => Self::ZERO,
-			Some(Ordering::Less) => Self::negative_n(n.abs() as usize),
+			Some(Ordering::Less) => Self::negative_n(n.unsigned_abs()),
 			None => Self::ZERO,
 		}
 	}
@@ -170,6 +228,144 @@ impl From<u8> for Fraction {
 		Self::from(n as usize)
 	}
 }
+impl From<Fraction> for f64 {
+	fn from(f: Fraction) -> Self {
+		f.as_f64()
+	}
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseFractionError {
+	/// Not a number like `-12`, `1_000`, `0.125`, `-3.5e-2` or two of them separated by `/`.
+	Invalid,
+	ZeroDenominator,
+	/// The exact value doesn't fit into a [`Fraction`], or the exponent is too large
+	/// for a [`BigFraction`](crate::big_fraction::BigFraction).
+	Overflow,
+}
+impl Display for ParseFractionError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.write_str(match self {
+			ParseFractionError::Invalid => "invalid number",
+			ParseFractionError::ZeroDenominator => "zero denominator",
+			ParseFractionError::Overflow => "number can't be represented exactly",
+		})
+	}
+}
+impl Error for ParseFractionError {}
+/// Parses integers, decimals and scientific notation exactly, `0.125` becomes `1/8`.
+/// Digits can be separated by `_` and two numbers can be divided with `/`.
+impl FromStr for Fraction {
+	type Err = ParseFractionError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.split_once('/') {
+			Some((n, d)) => {
+				let (n, d) = (parse_decimal(n)?, parse_decimal(d)?);
+				if d.sign() == 0 {
+					Err(ParseFractionError::ZeroDenominator)
+				} else {
+					n.checked_div(d).ok_or(ParseFractionError::Overflow)
+				}
+			}
+			None => parse_decimal(s),
+		}
+	}
+}
+/// A decimal split into `digits · 10^exponent`, see [`Decimal::parse`].
+pub(crate) struct Decimal {
+	pub negative: bool,
+	/// Without leading or trailing zeros, empty for zero.
+	pub digits: String,
+	/// `None` if it doesn't fit into an `i64`.
+	pub exponent: Option<i64>,
+}
+impl Decimal {
+	/// Parses a number like `-1_000.5e-3` without the `/` of [`Fraction::from_str`].
+	pub fn parse(s: &str) -> Result<Self, ParseFractionError> {
+		use ParseFractionError::Invalid;
+		let (negative, s) = match s.strip_prefix('-') {
+			Some(s) => (true, s),
+			None => (false, s.strip_prefix('+').unwrap_or(s)),
+		};
+		let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+			// `None` if the exponent doesn't fit into an `i64`, which is fine for zero
+			Some((m, e)) => match e.parse::<i64>().map_err(|e| *e.kind()) {
+				Ok(e) => (m, Some(e)),
+				Err(IntErrorKind::PosOverflow | IntErrorKind::NegOverflow) => (m, None),
+				Err(_) => return Err(Invalid),
+			},
+			None => (s, Some(0)),
+		};
+		let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+		if (int.is_empty() && frac.is_empty())
+			|| [int, frac].iter().any(|p| {
+				p.starts_with('_')
+					|| p.ends_with('_')
+					|| !p.chars().all(|c| c.is_ascii_digit() || c == '_')
+			}) {
+			return Err(Invalid);
+		}
+		let mut digits = int
+			.chars()
+			.chain(frac.chars())
+			.filter(|c| *c != '_')
+			.collect::<String>();
+		let frac_len = frac.chars().filter(|c| *c != '_').count() as i64;
+		let mut trailing_zeros = 0;
+		while digits.ends_with('0') {
+			digits.pop();
+			trailing_zeros += 1;
+		}
+		let digits = digits.trim_start_matches('0').to_string();
+		let exponent = exponent
+			.and_then(|e| e.checked_sub(frac_len))
+			.and_then(|e| e.checked_add(trailing_zeros));
+		Ok(Self {
+			negative,
+			digits,
+			exponent,
+		})
+	}
+}
+fn parse_decimal(s: &str) -> Result<Fraction, ParseFractionError> {
+	use ParseFractionError::Overflow;
+	let Decimal {
+		negative,
+		digits,
+		exponent,
+	} = Decimal::parse(s)?;
+	if digits.is_empty() {
+		return Ok(Fraction::ZERO);
+	}
+	let exponent = exponent.ok_or(Overflow)?;
+	let mut numerator = digits.parse::<usize>().map_err(|_| Overflow)?;
+	let pow = |base: usize, exp: u64| {
+		u32::try_from(exp)
+			.ok()
+			.and_then(|e| base.checked_pow(e))
+			.ok_or(Overflow)
+	};
+	if exponent >= 0 {
+		numerator = numerator
+			.checked_mul(pow(10, exponent as u64)?)
+			.ok_or(Overflow)?;
+		Ok(Fraction::new(negative, numerator, 1))
+	} else {
+		// 10^-k = 1 / (2^k * 5^k), cancel with the numerator first to not overflow needlessly
+		let k = exponent.unsigned_abs();
+		let twos = (numerator.trailing_zeros() as u64).min(k);
+		numerator >>= twos;
+		let mut fives = 0;
+		while fives < k && numerator % 5 == 0 {
+			numerator /= 5;
+			fives += 1;
+		}
+		let denominator = pow(2, k - twos)?
+			.checked_mul(pow(5, k - fives)?)
+			.ok_or(Overflow)?;
+		Ok(Fraction::new(negative, numerator, denominator))
+	}
+}
 impl Add<Fraction> for Fraction {
 	type Output = Fraction;
 
@@ -181,12 +377,9 @@ impl Add<Fraction> for Fraction {
 }
 impl AddAssign<Fraction> for Fraction {
 	fn add_assign(&mut self, rhs: Fraction) {
-		let new_num = self.numerator as isize * rhs.denominator as isize * self.sign()
-			+ rhs.numerator as isize * self.denominator as isize * rhs.sign();
-		self.negative = new_num < 0;
-		self.numerator = new_num.abs() as usize;
-		self.denominator *= rhs.denominator;
-		self.reduce();
+		*self = self
+			.checked_add(rhs)
+			.expect("attempt to add fractions with overflow");
 	}
 }
 impl Sub<Fraction> for Fraction {
@@ -212,10 +405,9 @@ impl Mul<Fraction> for Fraction {
 }
 impl MulAssign<Fraction> for Fraction {
 	fn mul_assign(&mut self, rhs: Fraction) {
-		self.negative ^= rhs.negative;
-		self.numerator *= rhs.numerator;
-		self.denominator *= rhs.denominator;
-		self.reduce();
+		*self = self
+			.checked_mul(rhs)
+			.expect("attempt to multiply fractions with overflow");
 	}
 }
 impl Div<Fraction> for Fraction {
@@ -276,10 +468,69 @@ impl Debug for Fraction {
 		write!(f, "{}", self)
 	}
 }
+/// Written like `"-3/4"`, read from such a string, an integer or `{"num": -3, "den": 4}`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Fraction {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Fraction {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(FractionVisitor)
+	}
+}
+#[cfg(feature = "serde")]
+pub(crate) struct FractionVisitor;
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for FractionVisitor {
+	type Value = Fraction;
+
+	fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.write_str("a fraction like \"-3/4\", an integer or {\"num\": -3, \"den\": 4}")
+	}
+
+	fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Fraction, E> {
+		v.parse().map_err(E::custom)
+	}
+
+	fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Fraction, E> {
+		Ok(Fraction::from(v))
+	}
+
+	fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Fraction, E> {
+		Ok(Fraction::from(v))
+	}
+
+	fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Fraction, A::Error> {
+		use serde::de::Error;
+		let (mut num, mut den) = (None::<i64>, None::<i64>);
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"num" if num.is_some() => return Err(A::Error::duplicate_field("num")),
+				"den" if den.is_some() => return Err(A::Error::duplicate_field("den")),
+				"num" => num = Some(map.next_value()?),
+				"den" => den = Some(map.next_value()?),
+				_ => return Err(A::Error::unknown_field(&key, &["num", "den"])),
+			}
+		}
+		let num = num.ok_or_else(|| A::Error::missing_field("num"))?;
+		let den = den.unwrap_or(1);
+		if den == 0 {
+			return Err(A::Error::custom(ParseFractionError::ZeroDenominator));
+		}
+		Ok(Fraction::new(
+			(num < 0) != (den < 0),
+			num.unsigned_abs() as usize,
+			den.unsigned_abs() as usize,
+		))
+	}
+}
 
 #[cfg(test)]
 mod test {
-	use crate::fraction::Fraction;
+	use crate::fraction::{Fraction, ParseFractionError};
 
 	#[test]
 	fn from_nom_denom_pair() {
@@ -326,4 +577,106 @@ mod test {
 			Fraction::new(false, 1, 2)
 		);
 	}
+
+	#[test]
+	fn zero_is_not_negative() {
+		assert!(!Fraction::positive_n(0).is_negative());
+		assert_eq!(Fraction::from(0).to_string(), "0");
+		assert_eq!((-Fraction::ZERO).to_string(), "0");
+	}
+
+	#[test]
+	fn checked() {
+		let big = Fraction::positive_n(usize::MAX);
+		assert_eq!(big.checked_add(Fraction::ONE), None);
+		assert_eq!(
+			big.checked_sub(Fraction::ONE),
+			Some(Fraction::positive_n(usize::MAX - 1))
+		);
+		assert_eq!(big.checked_mul(Fraction::positive_n(2)), None);
+		assert_eq!(
+			big.checked_mul(Fraction::positive(1, usize::MAX)),
+			Some(Fraction::ONE)
+		);
+		assert_eq!(Fraction::ONE.checked_div(Fraction::ZERO), None);
+		assert_eq!(
+			Fraction::positive(1, 3).checked_sub(Fraction::positive(1, 2)),
+			Some(Fraction::negative(1, 6))
+		);
+	}
+
+	#[test]
+	#[should_panic]
+	fn overflow_panics() {
+		let _ = Fraction::positive_n(usize::MAX) + Fraction::ONE;
+	}
+
+	#[test]
+	fn from_str() {
+		let parse = |s: &str| s.parse::<Fraction>();
+		assert_eq!(parse("0.125"), Ok(Fraction::positive(1, 8)));
+		assert_eq!(parse("-3.5e-2"), Ok(Fraction::negative(7, 200)));
+		assert_eq!(parse("1_000"), Ok(Fraction::positive_n(1000)));
+		assert_eq!(parse("+2E3"), Ok(Fraction::positive_n(2000)));
+		assert_eq!(parse(".5/-0.25"), Ok(Fraction::negative_n(2)));
+		assert_eq!(parse("-0.000"), Ok(Fraction::ZERO));
+		assert_eq!(
+			parse("0.5000000000000000000000000000000"),
+			Ok(Fraction::positive(1, 2))
+		);
+		assert_eq!(parse("1/0.0"), Err(ParseFractionError::ZeroDenominator));
+		assert_eq!(parse("1e30"), Err(ParseFractionError::Overflow));
+		assert_eq!(parse("1e-30"), Err(ParseFractionError::Overflow));
+		assert_eq!(
+			parse("10e9223372036854775807"),
+			Err(ParseFractionError::Overflow)
+		);
+		assert_eq!(
+			parse("1.5e-9223372036854775808"),
+			Err(ParseFractionError::Overflow)
+		);
+		assert_eq!(
+			parse("1e99999999999999999999"),
+			Err(ParseFractionError::Overflow)
+		);
+		assert_eq!(parse("0e99999999999999999999"), Ok(Fraction::ZERO));
+		assert_eq!(parse("-0.0e-99999999999999999999"), Ok(Fraction::ZERO));
+		for invalid in [
+			"", ".", "e3", "1e", "_1", "1_", "1_.5", "1._5", "1.5_", "1.2.3", "0x10", "--1",
+			"0e1.5",
+		] {
+			assert_eq!(
+				parse(invalid),
+				Err(ParseFractionError::Invalid),
+				"{invalid}"
+			);
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn serde() {
+		let json = |f: Fraction| serde_json::to_string(&f).unwrap();
+		let parse = |s: &str| serde_json::from_str::<Fraction>(s);
+		assert_eq!(json(Fraction::negative(3, 4)), r#""-3/4""#);
+		assert_eq!(json(Fraction::positive_n(2)), r#""2""#);
+		assert_eq!(parse(r#""-3/4""#).unwrap(), Fraction::negative(3, 4));
+		assert_eq!(parse(r#""0.125""#).unwrap(), Fraction::positive(1, 8));
+		assert_eq!(parse("-7").unwrap(), Fraction::negative_n(7));
+		assert_eq!(parse("7").unwrap(), Fraction::positive_n(7));
+		assert_eq!(
+			parse(r#"{"num": 3, "den": -6}"#).unwrap(),
+			Fraction::negative(1, 2)
+		);
+		assert_eq!(parse(r#"{"num": 3}"#).unwrap(), Fraction::positive_n(3));
+		for invalid in [
+			r#""1/0""#,
+			r#"{"num": 1, "den": 0}"#,
+			r#"{"den": 2}"#,
+			"0.5",
+			"[1]",
+		] {
+			assert!(parse(invalid).is_err(), "{invalid}");
+		}
+	}
 }
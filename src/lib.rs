@@ -1,20 +1,88 @@
-use matrix::Matrix;
-use solver::MatrixSolver;
+use matrix::{InverseError, Matrix, PivotStrategy};
+use scalar::Scalar;
+use solution::SolveOutcome;
+#[cfg(test)]
+use solver::debug_verify;
+use solver::{ArithmeticOverflow, MatrixSolver, Steps};
 
+pub mod big_fraction;
+pub mod big_uint;
+pub mod dense;
 pub mod fraction;
+pub mod lu;
 pub mod matrix;
+pub mod mod_p;
 pub mod solver;
 pub mod row;
+pub mod scalar;
+pub mod solution;
 pub mod parser;
 
-pub use parser::parse;
+pub use parser::{parse, ParseError};
 
-pub fn solve(m: Matrix) -> Matrix {
-	solve_with_history(m).get(-1)
+pub fn solve<T: Scalar>(m: Matrix<T>) -> Matrix<T> {
+	try_solve(m).unwrap_or_else(|e| panic!("{e}"))
 }
 
-pub fn solve_with_history(m: Matrix) -> MatrixSolver {
+pub fn solve_with_history<T: Scalar>(m: Matrix<T>) -> MatrixSolver<T> {
 	let mut s = MatrixSolver::new(m);
 	s.solve();
 	s
 }
+
+/// Solves a floating point system using partial pivoting,
+/// treating cells within `epsilon` of zero as zero.
+pub fn solve_float(m: Matrix<f64>, epsilon: f64) -> Matrix<f64> {
+	solve(
+		m.with_pivot_strategy(PivotStrategy::LargestMagnitude)
+			.with_tolerance(epsilon),
+	)
+}
+
+pub fn try_solve<T: Scalar>(mut m: Matrix<T>) -> Result<Matrix<T>, ArithmeticOverflow> {
+	#[cfg(test)]
+	let initial = m.clone();
+	solve_in_place(&mut m)?;
+	#[cfg(test)]
+	debug_verify(&initial, &m);
+	Ok(m)
+}
+
+pub fn try_solve_with_history<T: Scalar>(
+	m: Matrix<T>,
+) -> Result<MatrixSolver<T>, ArithmeticOverflow> {
+	let mut s = MatrixSolver::new(m);
+	s.try_solve()?;
+	Ok(s)
+}
+
+/// The inverse of the left side of `m`, see [`Matrix::inverse`].
+pub fn invert<T: Scalar>(m: Matrix<T>) -> Result<Matrix<T>, InverseError> {
+	m.inverse()
+}
+
+/// Solves `m` in place without keeping or cloning any steps, see [`Matrix::outcome`].
+/// On overflow `m` is left at the last step calculated.
+pub fn solve_in_place<T: Scalar>(
+	m: &mut Matrix<T>,
+) -> Result<Option<SolveOutcome<T>>, ArithmeticOverflow> {
+	let mut step = 1;
+	loop {
+		match m.try_step() {
+			Ok(true) => step += 1,
+			Ok(false) => return Ok(m.outcome()),
+			Err(cell) => {
+				return Err(ArithmeticOverflow {
+					step,
+					state: m.state().clone(),
+					cell,
+				})
+			}
+		}
+	}
+}
+
+/// Calculates the steps of `m` lazily, see [`Steps`].
+pub fn steps<T: Scalar>(m: Matrix<T>) -> Steps<T> {
+	Steps::new(m)
+}
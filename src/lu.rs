@@ -0,0 +1,257 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{
+	fraction::Fraction,
+	matrix::{is_odd_permutation, Cell, Matrix, PivotStrategy},
+	scalar::Scalar,
+};
+
+/// A factorization `P·A = L·U` of the square left side `A` of a [`Matrix`],
+/// so the same left side can be solved for many right sides without eliminating it again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuDecomposition<T = Fraction> {
+	l: Vec<Vec<T>>,
+	u: Vec<Vec<T>>,
+	permutation: Vec<usize>,
+}
+impl<T: Scalar> LuDecomposition<T> {
+	/// Decomposes the left side of `m`. The pivot of every column is chosen from the
+	/// remaining rows like [`Matrix::optimize_indeces`] does: the first one in
+	/// [`Matrix::row_sequence`] which isn't zero, or the largest one with
+	/// [`PivotStrategy::LargestMagnitude`].
+	pub fn new(m: &Matrix<T>) -> Result<Self, LuError> {
+		let n = m.rows().len();
+		if m.rows().iter().any(|r| r.left().len() != n) {
+			return Err(LuError::NotSquare);
+		}
+		let mut remaining = m.row_sequence().clone();
+		let mut permutation = Vec::with_capacity(n);
+		let mut l = vec![vec![T::zero(); n]; n];
+		// the rows of `A` which are eliminated step by step until they form `U`
+		let mut rows = m
+			.rows()
+			.iter()
+			.map(|r| r.left().clone())
+			.collect::<Vec<Vec<T>>>();
+		for k in 0..n {
+			let candidates = remaining
+				.iter()
+				.enumerate()
+				.filter(|(_, r)| !m.is_zero(&rows[**r][k]));
+			let pivot = match m.pivot_strategy() {
+				PivotStrategy::LargestMagnitude => candidates
+					.max_by(|(_, a), (_, b)| {
+						rows[**a][k]
+							.magnitude()
+							.total_cmp(&rows[**b][k].magnitude())
+					})
+					.map(|(i, _)| i),
+				_ => candidates.map(|(i, _)| i).next(),
+			}
+			.ok_or(LuError::Singular)?;
+			let pivot = remaining.remove(pivot);
+			let pivot_row = rows[pivot].clone();
+			for r in &remaining {
+				let overflow = |col: usize| {
+					LuError::Overflow(Cell {
+						row: *r,
+						col: col as isize,
+					})
+				};
+				let factor = rows[*r][k].checked_div(&pivot_row[k]).ok_or(overflow(k))?;
+				for (j, p) in pivot_row.iter().enumerate().skip(k) {
+					rows[*r][j] = factor
+						.checked_mul(p)
+						.and_then(|scaled| rows[*r][j].checked_sub(&scaled))
+						.ok_or(overflow(j))?;
+				}
+				rows[*r][k] = factor;
+			}
+			permutation.push(pivot);
+		}
+		// the factors were stored below the diagonal of `U`
+		let mut u = permutation
+			.iter()
+			.map(|r| rows[*r].clone())
+			.collect::<Vec<Vec<T>>>();
+		for (i, row) in u.iter_mut().enumerate() {
+			for (j, f) in row.iter_mut().take(i).enumerate() {
+				l[i][j] = std::mem::replace(f, T::zero());
+			}
+			l[i][i] = T::one();
+		}
+		Ok(Self { l, u, permutation })
+	}
+
+	/// The lower triangular factor with ones on its diagonal.
+	pub fn l(&self) -> &Vec<Vec<T>> {
+		&self.l
+	}
+
+	/// The upper triangular factor.
+	pub fn u(&self) -> &Vec<Vec<T>> {
+		&self.u
+	}
+
+	/// Row `i` of `P·A` is row `p()[i]` of `A`.
+	pub fn p(&self) -> &Vec<usize> {
+		&self.permutation
+	}
+
+	/// Like [`LuDecomposition::try_solve`], but panics if a value overflows.
+	pub fn solve(&self, rhs: &[T]) -> Vec<T> {
+		self.try_solve(rhs)
+			.expect("Overflow while solving the LU decomposition.")
+	}
+
+	/// Solves `A·x = rhs` with one forward and one backward substitution,
+	/// `None` if a value overflows.
+	pub fn try_solve(&self, rhs: &[T]) -> Option<Vec<T>> {
+		let n = self.u.len();
+		assert_eq!(rhs.len(), n, "The right side needs one value per row.");
+		let mut x = self
+			.permutation
+			.iter()
+			.map(|r| rhs[*r].clone())
+			.collect::<Vec<T>>();
+		for i in 0..n {
+			for j in 0..i {
+				x[i] = x[i].checked_sub(&self.l[i][j].checked_mul(&x[j])?)?;
+			}
+		}
+		for i in (0..n).rev() {
+			for j in i + 1..n {
+				x[i] = x[i].checked_sub(&self.u[i][j].checked_mul(&x[j])?)?;
+			}
+			x[i] = x[i].checked_div(&self.u[i][i])?;
+		}
+		Some(x)
+	}
+
+	/// Like [`LuDecomposition::try_determinant`], but panics if it overflows.
+	pub fn determinant(&self) -> T {
+		self.try_determinant()
+			.expect("Overflow while calculating the determinant.")
+	}
+
+	/// The product of the diagonal of `U`, negated if `P` is an odd permutation,
+	/// `None` if it overflows.
+	pub fn try_determinant(&self) -> Option<T> {
+		let product = self
+			.u
+			.iter()
+			.enumerate()
+			.try_fold(T::one(), |d, (i, r)| d.checked_mul(&r[i]))?;
+		if is_odd_permutation(&self.permutation) {
+			T::zero().checked_sub(&product)
+		} else {
+			Some(product)
+		}
+	}
+}
+
+/// Why a [`LuDecomposition`] couldn't be calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LuError {
+	/// The left side has more or less rows than cols.
+	NotSquare,
+	/// The left side has no inverse, so there is no pivot for one of the cols.
+	Singular,
+	/// The cell overflowed while it was eliminated.
+	Overflow(Cell),
+}
+impl Display for LuError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			LuError::NotSquare => write!(f, "The left side has to be square."),
+			LuError::Singular => write!(f, "The matrix is singular."),
+			LuError::Overflow(Cell { row, col }) => write!(
+				f,
+				"Overflow in row {} at index {col} while decomposing the matrix.",
+				row + 1
+			),
+		}
+	}
+}
+impl Error for LuError {}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		fraction::Fraction,
+		lu::{LuDecomposition, LuError},
+		matrix,
+		matrix::{Cell, PivotStrategy},
+		scalar::Scalar,
+	};
+
+	fn multiply<T: Scalar>(a: &[Vec<T>], b: &[Vec<T>]) -> Vec<Vec<T>> {
+		a.iter()
+			.map(|r| {
+				(0..b[0].len())
+					.map(|c| {
+						r.iter()
+							.zip(b)
+							.fold(T::zero(), |s, (f, row)| s + f.clone() * row[c].clone())
+					})
+					.collect()
+			})
+			.collect()
+	}
+
+	#[test]
+	fn decompose() {
+		let cut = matrix![[0; 2; 1] | [1], [1; 1; 0] | [1], [3; 0; 1] | [1]];
+		let lu = cut.lu().unwrap();
+		let permuted = lu
+			.p()
+			.iter()
+			.map(|r| cut.rows()[*r].left().clone())
+			.collect::<Vec<Vec<Fraction>>>();
+		assert_eq!(multiply(lu.l(), lu.u()), permuted);
+		for (i, r) in lu.l().iter().enumerate() {
+			assert!(r[i].is_one() && r[i + 1..].iter().all(Scalar::is_zero));
+		}
+		for (i, r) in lu.u().iter().enumerate() {
+			assert!(r[..i].iter().all(Scalar::is_zero));
+		}
+		assert_eq!(lu.determinant(), cut.determinant());
+		assert_eq!(
+			lu.solve(&[Fraction::ONE; 3]),
+			vec![
+				Fraction::positive(2, 5),
+				Fraction::positive(3, 5),
+				Fraction::negative(1, 5)
+			]
+		);
+		assert_eq!(
+			lu.solve(&[Fraction::from(3), Fraction::from(2), Fraction::from(4)]),
+			vec![Fraction::ONE, Fraction::ONE, Fraction::ONE]
+		);
+
+		assert_eq!(
+			matrix![[1; 2] | [1], [2; 4] | [1]].lu(),
+			Err::<LuDecomposition, LuError>(LuError::Singular)
+		);
+		assert_eq!(
+			matrix![[1; 1; 1] | [6], [1; 0; -1] | [-2]].lu(),
+			Err::<LuDecomposition, LuError>(LuError::NotSquare)
+		);
+		assert_eq!(
+			matrix![[1; i64::MAX] | [1], [i64::MAX; 1] | [1]].lu(),
+			Err::<LuDecomposition, LuError>(LuError::Overflow(Cell { row: 1, col: 1 }))
+		);
+	}
+
+	#[test]
+	fn partial_pivoting() {
+		let cut = matrix![f64; [1; 2] | [3], [4; 5] | [6]]
+			.with_pivot_strategy(PivotStrategy::LargestMagnitude);
+		let lu = LuDecomposition::new(&cut).unwrap();
+		assert_eq!(lu.p(), &vec![1, 0]);
+		assert_eq!(lu.l(), &vec![vec![1.0, 0.0], vec![0.25, 1.0]]);
+		assert!((lu.determinant() + 3.0).abs() < 1e-12);
+		let x = lu.solve(&[3.0, 6.0]);
+		assert!((x[0] + 1.0).abs() < 1e-12 && (x[1] - 2.0).abs() < 1e-12);
+	}
+}
@@ -1,12 +1,479 @@
-use matrix_solver::{solve_with_history, matrix};
-
-fn main() {
-	println!(
-		"{}",
-		solve_with_history(matrix![
-			[1;1;1]|[1],
-			[1;1;32]|[342],
-			[2;8;5]|[124],
-		])
-	)
-}
\ No newline at end of file
+use std::{
+	env,
+	fmt::Display,
+	fs,
+	io::{self, Read},
+	process::ExitCode,
+};
+
+mod repl;
+
+use matrix_solver::{
+	big_fraction::BigFraction,
+	fraction::Fraction,
+	matrix::PivotStrategy,
+	parser::{parse_equations_with_pivot_strategy, parse_with_pivot_strategy},
+	scalar::Scalar,
+	solution::SolveOutcome,
+	solver::{MatrixSolver, RightSides},
+};
+
+const HELP: &str = "\
+Solves a system of linear equations with Gauss-Jordan elimination.
+
+Usage: matrix_solver [OPTIONS] [FILE]
+       matrix_solver repl
+
+Reads the system from FILE, or from stdin if FILE is missing or `-`.
+Each line is a row like `(1;2/3;-0.5|4)`, or an equation like `2x + 3y = 5` with --equations.
+`repl` starts an interactive session to go through the elimination step by step.
+
+Options:
+  -o, --output <MODE>  final (default): only the solution
+                       history: every step followed by the solution
+                       json: the solution as JSON (needs the `serde` feature)
+                       json-history: every matrix with its state and row
+                       operations as JSON (needs the `serde` feature)
+                       latex: every step and the solution as LaTeX
+  -e, --equations      Read equations with named variables instead of rows
+  -j, --json           Read a JSON array of rows like {\"left\": [\"1\", \"2/3\"], \"right\": [4]}
+                       instead of text rows (needs the `serde` feature)
+  -i, --independent    Solve every right side on its own instead of treating
+                       the right sides after the first as parameters t, u, ...
+  -b, --big            Calculate with fractions of any size, which never overflow
+                       but are slower
+  -p, --pivot <ORDER>  max-zeros (default): most zeros, tries every column order
+                       greedy: most zeros, one column after another
+                       natural: rows and columns in the order they were entered
+                       smallest: smallest non-zero cell first
+                       markowitz: fewest new non-zero cells first
+                       largest: largest cell in each column (partial pivoting)
+  -h, --help           Print this help
+
+Exit codes:
+  0  the system has one or infinitely many solutions
+     (with --independent: every right side has)
+  1  invalid arguments or the input can't be read
+  2  the input can't be parsed
+  3  the system has no solution (with --independent: some right side hasn't)
+  4  solving failed, e.g. because a number overflowed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+	Final,
+	History,
+	#[cfg(feature = "serde")]
+	Json,
+	#[cfg(feature = "serde")]
+	JsonHistory,
+	Latex,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+	file: Option<String>,
+	output: Output,
+	equations: bool,
+	json: bool,
+	independent: bool,
+	big: bool,
+	pivot: PivotStrategy,
+	repl: bool,
+}
+
+/// The scalars the binary can calculate with, see `--big`.
+#[cfg(not(feature = "serde"))]
+trait Number: Scalar + TryFrom<BigFraction, Error: Display> {}
+#[cfg(not(feature = "serde"))]
+impl<T: Scalar + TryFrom<BigFraction, Error: Display>> Number for T {}
+/// The scalars the binary can calculate with, see `--big`.
+#[cfg(feature = "serde")]
+trait Number:
+	Scalar + TryFrom<BigFraction, Error: Display> + serde::Serialize + serde::de::DeserializeOwned
+{
+}
+#[cfg(feature = "serde")]
+impl<
+		T: Scalar
+			+ TryFrom<BigFraction, Error: Display>
+			+ serde::Serialize
+			+ serde::de::DeserializeOwned,
+	> Number for T
+{
+}
+
+/// `Ok(None)` if the help was requested.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Options>, String> {
+	let mut options = Options {
+		file: None,
+		output: Output::Final,
+		equations: false,
+		json: false,
+		independent: false,
+		big: false,
+		pivot: PivotStrategy::default(),
+		repl: false,
+	};
+	let mut args = args.into_iter().peekable();
+	if args.peek().is_some_and(|a| a == "repl") {
+		args.next();
+		options.repl = true;
+	}
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"-h" | "--help" => return Ok(None),
+			"-e" | "--equations" => options.equations = true,
+			"-i" | "--independent" => options.independent = true,
+			"-b" | "--big" => options.big = true,
+			#[cfg(feature = "serde")]
+			"-j" | "--json" => options.json = true,
+			#[cfg(not(feature = "serde"))]
+			"-j" | "--json" => return Err(format!("`{arg}` needs the `serde` feature.")),
+			"-o" | "--output" => {
+				options.output = match args.next().as_deref() {
+					Some("final") => Output::Final,
+					Some("history") => Output::History,
+					#[cfg(feature = "serde")]
+					Some("json") => Output::Json,
+					#[cfg(feature = "serde")]
+					Some("json-history") => Output::JsonHistory,
+					#[cfg(not(feature = "serde"))]
+					Some(mode @ ("json" | "json-history")) => {
+						return Err(format!("`{mode}` needs the `serde` feature."))
+					}
+					Some("latex") => Output::Latex,
+					Some(mode) => return Err(format!("Unknown output mode `{mode}`.")),
+					None => return Err(format!("`{arg}` needs an output mode.")),
+				}
+			}
+			"-p" | "--pivot" => {
+				options.pivot = match args.next().as_deref() {
+					Some("max-zeros") => PivotStrategy::MaxZeros,
+					Some("greedy") => PivotStrategy::GreedyMaxZeros,
+					Some("natural") => PivotStrategy::Natural,
+					Some("smallest") => PivotStrategy::SmallestEntry,
+					Some("markowitz") => PivotStrategy::Markowitz,
+					Some("largest") => PivotStrategy::LargestMagnitude,
+					Some(order) => return Err(format!("Unknown pivot order `{order}`.")),
+					None => return Err(format!("`{arg}` needs a pivot order.")),
+				}
+			}
+			"-" => options.file = None,
+			_ if arg.starts_with('-') => return Err(format!("Unknown option `{arg}`.")),
+			_ if options.file.is_some() => return Err(format!("Unexpected argument `{arg}`.")),
+			_ => options.file = Some(arg),
+		}
+	}
+	if options.equations && options.json {
+		return Err(String::from(
+			"`--equations` and `--json` can't be combined.",
+		));
+	}
+	Ok(Some(options))
+}
+
+/// A JSON array of rows, every cell is read like [`Fraction`]'s `Deserialize`.
+#[cfg(feature = "serde")]
+fn parse_json<T: Number>(
+	input: &str,
+	pivot: PivotStrategy,
+) -> Result<matrix_solver::matrix::Matrix<T>, String> {
+	use matrix_solver::matrix::Matrix;
+
+	let rows = serde_json::from_str::<Vec<matrix_solver::row::Row<T>>>(input)
+		.map_err(|e| format!("Invalid JSON: {e}"))?;
+	if let Some(first) = rows.first() {
+		if let Some(i) = rows.iter().position(|r| {
+			r.left().len() != first.left().len() || r.right().len() != first.right().len()
+		}) {
+			return Err(format!(
+				"Row {} needs {} cells on the left and {} on the right.",
+				i + 1,
+				first.left().len(),
+				first.right().len()
+			));
+		}
+	}
+	Ok(Matrix::create_with_pivot_strategy(rows, pivot))
+}
+
+/// Every matrix of `solver` with its state and the row operations leading to it.
+#[cfg(feature = "serde")]
+fn json_history<T: Number>(solver: &MatrixSolver<T>) -> String {
+	serde_json::to_string(solver).expect("The history only contains strings and numbers.")
+}
+
+/// The outcome like [`SolveOutcome`]'s `Serialize`, with the names of the variables.
+#[cfg(feature = "serde")]
+fn json<T: Number>(outcome: &SolveOutcome<T>, variables: &[String]) -> String {
+	#[derive(serde::Serialize)]
+	struct Json<'a, T: Number> {
+		#[serde(flatten)]
+		outcome: &'a SolveOutcome<T>,
+		variables: &'a [String],
+	}
+	serde_json::to_string(&Json { outcome, variables })
+		.expect("The outcome only contains strings and numbers.")
+}
+
+/// One outcome per right side, see [`RightSides::Independent`].
+#[cfg(feature = "serde")]
+fn json_independent<T: Number>(outcomes: &[SolveOutcome<T>], variables: &[String]) -> String {
+	#[derive(serde::Serialize)]
+	struct Json<'a, T: Number> {
+		variables: &'a [String],
+		right_sides: &'a [SolveOutcome<T>],
+	}
+	serde_json::to_string(&Json {
+		variables,
+		right_sides: outcomes,
+	})
+	.expect("The outcomes only contain strings and numbers.")
+}
+
+fn run(options: Options) -> ExitCode {
+	let input = match &options.file {
+		Some(file) => fs::read_to_string(file).map_err(|e| format!("Can't read `{file}`: {e}")),
+		None => {
+			let mut input = String::new();
+			io::stdin()
+				.read_to_string(&mut input)
+				.map(|_| input)
+				.map_err(|e| format!("Can't read stdin: {e}"))
+		}
+	};
+	let input = match input {
+		Ok(input) => input,
+		Err(e) => {
+			eprintln!("{e}");
+			return ExitCode::from(1);
+		}
+	};
+	if options.big {
+		solve::<BigFraction>(&options, &input)
+	} else {
+		solve::<Fraction>(&options, &input)
+	}
+}
+
+/// Parses, solves and prints `input` calculating with `T`.
+fn solve<T: Number>(options: &Options, input: &str) -> ExitCode {
+	let pivot = options.pivot;
+	let parsed = match options {
+		Options {
+			equations: true, ..
+		} => parse_equations_with_pivot_strategy::<T>(input, pivot).map_err(|e| e.to_string()),
+		#[cfg(feature = "serde")]
+		Options { json: true, .. } => parse_json::<T>(input, pivot).map(|m| (m, Vec::new())),
+		_ => parse_with_pivot_strategy::<T>(input, pivot)
+			.map(|m| (m, Vec::new()))
+			.map_err(|e| e.to_string()),
+	};
+	let (matrix, names) = match parsed {
+		Ok(parsed) => parsed,
+		Err(e) => {
+			eprintln!("{e}");
+			return ExitCode::from(2);
+		}
+	};
+	let mut solver = MatrixSolver::new(matrix)
+		.with_variable_names(names)
+		.with_right_sides(if options.independent {
+			RightSides::Independent
+		} else {
+			RightSides::Parametric
+		});
+	let outcome = match solver.try_solve() {
+		Ok(Some(outcome)) => outcome,
+		Ok(None) => {
+			eprintln!("{solver:#}");
+			return ExitCode::from(4);
+		}
+		Err(e) => {
+			eprintln!("{e}");
+			return ExitCode::from(4);
+		}
+	};
+	let outcomes = match solver.right_sides() {
+		RightSides::Parametric => vec![outcome],
+		RightSides::Independent => solver.outcomes().unwrap_or_default(),
+	};
+	#[cfg(feature = "serde")]
+	let variables = (0..solver[0].col_sequence().len())
+		.map(|i| solver.variable_name(i))
+		.collect::<Vec<String>>();
+	match options.output {
+		Output::Final => print!("{solver:#}"),
+		Output::History => print!("{solver}"),
+		Output::Latex => println!("{}", solver.to_latex()),
+		#[cfg(feature = "serde")]
+		Output::Json if options.independent => {
+			println!("{}", json_independent(&outcomes, &variables))
+		}
+		#[cfg(feature = "serde")]
+		Output::Json => println!("{}", json(&outcomes[0], &variables)),
+		#[cfg(feature = "serde")]
+		Output::JsonHistory => println!("{}", json_history(&solver)),
+	}
+	if outcomes
+		.iter()
+		.any(|o| matches!(o, SolveOutcome::Inconsistent { .. }))
+	{
+		ExitCode::from(3)
+	} else {
+		ExitCode::SUCCESS
+	}
+}
+
+fn main() -> ExitCode {
+	match parse_args(env::args().skip(1)) {
+		Ok(Some(Options { repl: true, .. })) => {
+			match repl::run(io::stdin().lock(), &mut io::stdout()) {
+				Ok(()) => ExitCode::SUCCESS,
+				Err(e) => {
+					eprintln!("{e}");
+					ExitCode::from(1)
+				}
+			}
+		}
+		Ok(Some(options)) => run(options),
+		Ok(None) => {
+			println!("{HELP}");
+			ExitCode::SUCCESS
+		}
+		Err(e) => {
+			eprintln!("{e}\n\n{HELP}");
+			ExitCode::from(1)
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use matrix_solver::matrix::PivotStrategy;
+
+	use crate::{parse_args, Options, Output};
+
+	fn args(args: &[&str]) -> Result<Option<Options>, String> {
+		parse_args(args.iter().map(|a| a.to_string()))
+	}
+
+	#[test]
+	fn arguments() {
+		assert_eq!(
+			args(&[]),
+			Ok(Some(Options {
+				file: None,
+				output: Output::Final,
+				equations: false,
+				json: false,
+				independent: false,
+				big: false,
+				pivot: PivotStrategy::MaxZeros,
+				repl: false
+			}))
+		);
+		assert_eq!(
+			args(&["-o", "history", "-e", "system.txt"]),
+			Ok(Some(Options {
+				file: Some(String::from("system.txt")),
+				output: Output::History,
+				equations: true,
+				json: false,
+				independent: false,
+				big: false,
+				pivot: PivotStrategy::MaxZeros,
+				repl: false
+			}))
+		);
+		assert!(args(&["repl"]).unwrap().unwrap().repl);
+		assert!(args(&["--independent"]).unwrap().unwrap().independent);
+		assert!(args(&["-b"]).unwrap().unwrap().big);
+		assert_eq!(
+			args(&["-p", "markowitz"]).unwrap().unwrap().pivot,
+			PivotStrategy::Markowitz
+		);
+		assert!(args(&["--pivot", "random"]).is_err());
+		assert_eq!(args(&["--output", "history", "--help"]), Ok(None));
+		assert!(args(&["--output"]).is_err());
+		assert!(args(&["--output", "xml"]).is_err());
+		assert!(args(&["--verbose"]).is_err());
+		assert!(args(&["a.txt", "b.txt"]).is_err());
+		assert_eq!(args(&["--json"]).is_ok(), cfg!(feature = "serde"));
+		assert_eq!(args(&["-o", "json"]).is_ok(), cfg!(feature = "serde"));
+		assert_eq!(
+			args(&["-o", "json-history"]).is_ok(),
+			cfg!(feature = "serde")
+		);
+		assert!(args(&["-j", "-e"]).is_err());
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn json_output() {
+		use matrix_solver::{fraction::Fraction, solution::SolveOutcome};
+
+		use crate::{json, json_independent};
+
+		let names = [String::from("x"), String::from("y\"")];
+		assert_eq!(
+			json(
+				&SolveOutcome::Unique {
+					solution: vec![vec![Fraction::negative(3, 4), Fraction::ONE]]
+				},
+				&names
+			),
+			r#"{"outcome":"unique","solution":[["-3/4","1"]],"variables":["x","y\""]}"#
+		);
+		assert_eq!(
+			json::<Fraction>(&SolveOutcome::Inconsistent { row: 1 }, &names),
+			r#"{"outcome":"inconsistent","row":1,"variables":["x","y\""]}"#
+		);
+		assert_eq!(
+			json_independent(
+				&[
+					SolveOutcome::Unique {
+						solution: vec![vec![Fraction::ONE, Fraction::ZERO]]
+					},
+					SolveOutcome::Inconsistent { row: 0 }
+				],
+				&names
+			),
+			r#"{"variables":["x","y\""],"right_sides":[{"outcome":"unique","solution":[["1","0"]]},{"outcome":"inconsistent","row":0}]}"#
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn json_input() {
+		use matrix_solver::{fraction::Fraction, solution::SolveOutcome, solver::MatrixSolver};
+
+		use crate::{json_history, parse_json};
+
+		let m = parse_json(
+			r#"[{"left": ["1", 1], "right": ["3/2"]}, {"left": [1, -1], "right": [{"num": 1, "den": 2}]}]"#,
+			PivotStrategy::default(),
+		)
+		.unwrap();
+		let mut solver = MatrixSolver::new(m);
+		assert_eq!(
+			solver.solve(),
+			Some(SolveOutcome::Unique {
+				solution: vec![vec![Fraction::ONE, Fraction::positive(1, 2)]]
+			})
+		);
+		let history = serde_json::from_str::<MatrixSolver>(&json_history(&solver)).unwrap();
+		assert_eq!(history.to_string(), solver.to_string());
+		assert_eq!(history[-1].rows(), solver[-1].rows());
+		assert!(parse_json::<Fraction>(
+			r#"[{"left": [1], "right": []}, {"left": [1, 2], "right": []}]"#,
+			PivotStrategy::default()
+		)
+		.is_err());
+		assert!(parse_json::<Fraction>(
+			r#"[{"left": ["x"], "right": []}]"#,
+			PivotStrategy::default()
+		)
+		.is_err());
+	}
+}
@@ -1,20 +1,58 @@
-use std::fmt::{Debug, Display};
+use std::{
+	error::Error,
+	fmt::{Debug, Display},
+};
 
-use crate::{fraction::Fraction, row::Row};
+use crate::{
+	fraction::Fraction,
+	lu::{LuDecomposition, LuError},
+	row::Row,
+	scalar::Scalar,
+	solution::{GeneralSolution, SolveOutcome},
+	solver::ArithmeticOverflow,
+};
 
-fn recursive_determine_best(cols: Vec<usize>, rows: &[Row], max: usize) -> (Vec<usize>, usize) {
+/// Like [`recursive_determine_best`], but always takes the first best col.
+fn greedy_determine_best<T: Scalar>(
+	mut cols: Vec<usize>,
+	rows: &[Row<T>],
+	max: usize,
+	is_zero: &impl Fn(&T) -> bool,
+) -> Vec<usize> {
+	while let Some(best) = (0..max)
+		.filter(|i| !cols.contains(i))
+		.map(|i| {
+			let zeros = rows
+				.iter()
+				.filter(|r| cols.iter().all(|c| is_zero(&r.left()[*c])))
+				.filter(|r| is_zero(&r.left()[i]))
+				.count();
+			(i, zeros)
+		})
+		.reduce(|best, i| if i.1 > best.1 { i } else { best })
+	{
+		cols.push(best.0);
+	}
+	cols
+}
+
+fn recursive_determine_best<T: Scalar>(
+	cols: Vec<usize>,
+	rows: &[Row<T>],
+	max: usize,
+	is_zero: &impl Fn(&T) -> bool,
+) -> (Vec<usize>, usize) {
 	if cols.len() == max {
 		return (cols, 0);
 	}
 	let mut best = (0..max)
-		.into_iter()
 		.filter(|i| cols.iter().all(|c| c != i))
 		.map(|i| {
 			(
 				i,
 				rows.iter()
-					.filter(|r| cols.iter().all(|i| r.left()[*i] == Fraction::ZERO))
-					.filter(|r| r.left()[i] == Fraction::ZERO)
+					.filter(|r| cols.iter().all(|i| is_zero(&r.left()[*i])))
+					.filter(|r| is_zero(&r.left()[i]))
 					.count(),
 			)
 		})
@@ -27,91 +65,292 @@ fn recursive_determine_best(cols: Vec<usize>, rows: &[Row], max: usize) -> (Vec<
 		.map(|(i, v)| {
 			let mut new_cols = cols.clone();
 			new_cols.push(*i);
-			let (new_cols, nulls) = recursive_determine_best(new_cols, rows, max);
+			let (new_cols, nulls) = recursive_determine_best(new_cols, rows, max, is_zero);
 			(new_cols, nulls + *v)
 		})
 		.max_by_key(|(_, i)| *i)
 		.unwrap()
 }
 
+/// How [`Matrix::optimize_indeces`] chooses the order of the pivots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PivotStrategy {
+	/// Keeps the order of the rows and columns, only skipping zero pivots.
+	/// Steps look like they would on paper.
+	Natural,
+	/// Orders rows and columns to maximize the amount of cells which are already zero.
+	/// Tries every column order, so it gets slow with more than about 8 columns.
+	#[default]
+	MaxZeros,
+	/// Like [`PivotStrategy::MaxZeros`], but always takes the column with the most
+	/// zeros next instead of trying every order.
+	GreedyMaxZeros,
+	/// Uses the non-zero cell with the smallest magnitude as the next pivot,
+	/// which keeps fractions small if it is e.g. `1`.
+	SmallestEntry,
+	/// Uses the cell with the smallest Markowitz cost `(r - 1)(c - 1)` as the next pivot,
+	/// `r` and `c` being the amount of non-zero cells in its remaining row and col.
+	/// Creates few non-zero cells in sparse systems.
+	Markowitz,
+	/// Partial pivoting: keeps the column order and uses the remaining row with the
+	/// largest magnitude in the pivot column. Rows are eliminated with
+	/// `row - pivot_row * (cell / pivot)` instead of the fraction-free combination,
+	/// which keeps floating point values small.
+	LargestMagnitude,
+}
+
 #[derive(Clone)]
-pub struct Matrix {
-	rows: Vec<Row>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+	feature = "serde",
+	serde(
+		try_from = "RawMatrix<T>",
+		bound(deserialize = "T: Scalar + serde::Deserialize<'de>")
+	)
+)]
+pub struct Matrix<T = Fraction> {
+	rows: Vec<Row<T>>,
 	state: MatrixState,
 	row_sequence: Vec<usize>,
 	col_sequence: Vec<usize>,
+	pivot_strategy: PivotStrategy,
+	tolerance: f64,
+	operations: Vec<RowOperation<T>>,
 }
-impl Matrix {
-	pub fn create<const N: usize, const M: usize>(
-		left: [[Fraction; N]; N],
-		right: [[Fraction; M]; N],
+impl<T: Scalar> Matrix<T> {
+	/// Creates a system of `R` equations with `C` unknowns and `M` right sides.
+	pub fn create<const R: usize, const C: usize, const M: usize>(
+		left: [[T; C]; R],
+		right: [[T; M]; R],
 	) -> Self {
 		Self::create_with_rows(
 			left.into_iter()
-				.zip(right.into_iter())
+				.zip(right)
 				.map(|(l, r)| Row::new(l.to_vec(), r.to_vec()))
-				.collect::<Vec<Row>>(),
+				.collect::<Vec<Row<T>>>(),
 		)
 	}
 
-	pub fn create_with_rows(rows: Vec<Row>) -> Self {
+	/// Orders the pivots with the default [`PivotStrategy::MaxZeros`], which gets slow
+	/// with many cols, see [`Matrix::create_with_pivot_strategy`].
+	pub fn create_with_rows(rows: Vec<Row<T>>) -> Self {
+		Self::create_with_pivot_strategy(rows, PivotStrategy::default())
+	}
+
+	/// Like [`Matrix::create_with_rows`], but orders the pivots only with `strategy`.
+	pub fn create_with_pivot_strategy(rows: Vec<Row<T>>, strategy: PivotStrategy) -> Self {
 		let mut new = Self::new_with_rows(rows);
+		new.pivot_strategy = strategy;
 		new.optimize_indeces();
+		new.update_state();
 		new
 	}
 
+	/// Uses `strategy` to choose the pivots for this and all following steps.
+	/// The pivots of a new matrix were already ordered with its previous strategy,
+	/// so use [`Matrix::create_with_pivot_strategy`] for large systems.
+	pub fn with_pivot_strategy(mut self, strategy: PivotStrategy) -> Self {
+		self.pivot_strategy = strategy;
+		self.optimize_indeces();
+		self.update_state();
+		self
+	}
+
+	/// Treats every cell whose [`Scalar::magnitude`] is at most `tolerance` as zero,
+	/// for this and all following steps.
+	pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+		self.tolerance = tolerance;
+		self.optimize_indeces();
+		self.update_state();
+		self
+	}
+
+	pub fn pivot_strategy(&self) -> PivotStrategy {
+		self.pivot_strategy
+	}
+
+	pub fn tolerance(&self) -> f64 {
+		self.tolerance
+	}
+
+	pub(crate) fn is_zero(&self, f: &T) -> bool {
+		f.is_zero() || (self.tolerance > 0.0 && f.magnitude() <= self.tolerance)
+	}
+
+	fn is_one(&self, f: &T) -> bool {
+		self.is_zero(&(f.clone() - T::one()))
+	}
+
 	pub fn optimize_indeces(&mut self) {
-		if let Some(max) = self.rows.iter().map(|r| r.left().len()).max() {
-			let cols = recursive_determine_best(Vec::new(), &self.rows, max).0;
-			let mut rows = self
-				.rows
-				.iter()
-				.map(|r| {
-					cols.iter()
-						.take_while(|i| {
-							r.left().get(**i).unwrap_or(&Fraction::positive_n(1)) == &Fraction::ZERO
-						})
-						.count()
+		match self.pivot_strategy {
+			PivotStrategy::Natural => {
+				self.optimize_greedy(|m, rows, cols| m.pivot_candidates(rows, cols).next())
+			}
+			PivotStrategy::MaxZeros => self.optimize_max_zeros(false),
+			PivotStrategy::GreedyMaxZeros => self.optimize_max_zeros(true),
+			PivotStrategy::LargestMagnitude => self.optimize_greedy(|m, rows, cols| {
+				let (_, col) = m.pivot_candidates(rows, cols).next()?;
+				m.pivot_candidates(rows, cols)
+					.filter(|(_, c)| *c == col)
+					// the first one of the largest
+					.min_by(|a, b| {
+						m.magnitude(rows, cols, *b)
+							.total_cmp(&m.magnitude(rows, cols, *a))
+					})
+			}),
+			PivotStrategy::SmallestEntry => self.optimize_greedy(|m, rows, cols| {
+				m.pivot_candidates(rows, cols).min_by(|a, b| {
+					m.magnitude(rows, cols, *a)
+						.total_cmp(&m.magnitude(rows, cols, *b))
 				})
+			}),
+			PivotStrategy::Markowitz => self.optimize_greedy(|m, rows, cols| {
+				let mut row_counts = vec![0; rows.len()];
+				let mut col_counts = vec![0; cols.len()];
+				for (r, c) in m.pivot_candidates(rows, cols) {
+					row_counts[r] += 1;
+					col_counts[c] += 1;
+				}
+				m.pivot_candidates(rows, cols)
+					.min_by_key(|(r, c)| (row_counts[*r] - 1) * (col_counts[*c] - 1))
+			}),
+		}
+	}
+
+	/// The amount of pivots which were already used and can't be reordered anymore.
+	fn fixed_pivots(&self) -> usize {
+		let pivots = self.row_sequence.len().min(self.col_sequence.len());
+		match self.state {
+			MatrixState::Initial => 0,
+			MatrixState::Null(n) => n.min(pivots),
+			_ => pivots,
+		}
+	}
+
+	/// The amount of unknowns.
+	fn width(&self) -> usize {
+		self.rows.first().map_or(0, |r| r.left().len())
+	}
+
+	fn leading_zeros(&self, row: usize) -> usize {
+		self.col_sequence
+			.iter()
+			.take_while(|c| self.is_zero(&self.rows[row].left()[**c]))
+			.count()
+	}
+
+	/// The amount of rows which aren't zero on the left side.
+	/// Once all cols are nulled, these are the rows with a pivot.
+	fn pivot_count(&self) -> usize {
+		self.rows
+			.iter()
+			.filter(|r| r.left().iter().any(|f| !self.is_zero(f)))
+			.count()
+	}
+
+	/// Chooses the remaining pivots one after another. `choose` gets the remaining rows and
+	/// cols, both ascending, and returns the positions of the next pivot's row and col in them.
+	/// Cols without a pivot are moved behind the others.
+	fn optimize_greedy(
+		&mut self,
+		choose: impl Fn(&Self, &[usize], &[usize]) -> Option<(usize, usize)>,
+	) {
+		let fixed = self.fixed_pivots();
+		let mut rows = self.row_sequence.split_off(fixed);
+		rows.sort_unstable();
+		let mut cols = self.col_sequence.split_off(fixed);
+		cols.sort_unstable();
+		while let Some((r, c)) = choose(self, &rows, &cols) {
+			self.row_sequence.push(rows.remove(r));
+			self.col_sequence.push(cols.remove(c));
+		}
+		self.row_sequence.append(&mut rows);
+		self.col_sequence.append(&mut cols);
+	}
+
+	/// The positions of the non-zero cells in `rows` and `cols`, col by col.
+	fn pivot_candidates<'a>(
+		&'a self,
+		rows: &'a [usize],
+		cols: &'a [usize],
+	) -> impl Iterator<Item = (usize, usize)> + 'a {
+		cols.iter().enumerate().flat_map(move |(c, col)| {
+			rows.iter()
 				.enumerate()
-				.collect::<Vec<(usize, usize)>>();
-			rows.sort_unstable_by_key(|(_, i)| *i);
-			self.col_sequence = cols;
-			self.row_sequence = rows.iter().map(|(i, _)| *i).collect::<Vec<usize>>();
+				.filter(move |(_, row)| !self.is_zero(&self.rows[**row].left()[*col]))
+				.map(move |(r, _)| (r, c))
+		})
+	}
+
+	fn magnitude(&self, rows: &[usize], cols: &[usize], (r, c): (usize, usize)) -> f64 {
+		self.rows[rows[r]].left()[cols[c]].magnitude()
+	}
+
+	fn optimize_max_zeros(&mut self, greedy: bool) {
+		let fixed = self.fixed_pivots();
+		let is_zero = |f: &T| self.is_zero(f);
+		let prefix = self.col_sequence[..fixed].to_vec();
+		let cols = if greedy {
+			greedy_determine_best(prefix, &self.rows, self.width(), &is_zero)
 		} else {
-			self.col_sequence = Vec::new();
-			self.row_sequence = Vec::new();
+			recursive_determine_best(prefix, &self.rows, self.width(), &is_zero).0
+		};
+		let mut rows = self
+			.rows
+			.iter()
+			.map(|r| {
+				cols.iter()
+					.take_while(|i| r.left().get(**i).is_some_and(is_zero))
+					.count()
+			})
+			.enumerate()
+			.collect::<Vec<(usize, usize)>>();
+		rows.sort_unstable_by_key(|(_, i)| *i);
+		self.col_sequence = cols;
+		self.row_sequence = rows.iter().map(|(i, _)| *i).collect::<Vec<usize>>();
+		self.skip_free_columns(fixed);
+	}
+
+	/// Moves cols which are zero in all remaining rows behind the others,
+	/// so every pivot is non-zero even if the left side is singular or not square.
+	fn skip_free_columns(&mut self, from: usize) {
+		let width = self.col_sequence.len();
+		for p in from..self.row_sequence.len().min(width) {
+			let zeros = self.leading_zeros(self.row_sequence[p]);
+			if zeros == width || zeros < p {
+				break;
+			}
+			let free = self.col_sequence.drain(p..zeros).collect::<Vec<usize>>();
+			self.col_sequence.extend(free);
 		}
 	}
 
-	pub fn new_with_rows(rows: Vec<Row>) -> Self {
-		rows.iter()
-			.enumerate()
-			.map(|(i, r)| (i, r.left().len()))
-			.for_each(|(i, e)| {
-				if e < rows.len() {
+	pub fn new_with_rows(rows: Vec<Row<T>>) -> Self {
+		if let Some(first) = rows.first() {
+			let (left, right) = (first.left().len(), first.right().len());
+			rows.iter().enumerate().for_each(|(i, r)| {
+				if r.left().len() != left || r.right().len() != right {
 					panic!(
-						"Row {} needs at least {} elements on the left side.",
+						"Row {} needs {} elements on the left and {} on the right side.",
 						i + 1,
-						rows.len()
+						left,
+						right
 					)
 				}
 			});
-		let starting_state = if rows.len() == 1 {
-			MatrixState::NormalizeRow(0)
-		} else {
-			MatrixState::Null(0)
-		};
-		Self::new_with_state(rows, starting_state)
+		}
+		Self::new_with_state(rows, MatrixState::Null(0))
 	}
 
-	pub fn new_with_state(rows: Vec<Row>, state: MatrixState) -> Self {
-		let row_sequence = (0..rows.len()).into_iter().collect::<Vec<usize>>();
-		Self::new(rows, state, row_sequence.clone(), row_sequence)
+	pub fn new_with_state(rows: Vec<Row<T>>, state: MatrixState) -> Self {
+		let row_sequence = (0..rows.len()).collect::<Vec<usize>>();
+		let col_sequence = (0..rows.first().map_or(0, |r| r.left().len())).collect::<Vec<usize>>();
+		Self::new(rows, state, row_sequence, col_sequence)
 	}
 
 	pub fn new(
-		rows: Vec<Row>,
+		rows: Vec<Row<T>>,
 		state: MatrixState,
 		row_sequence: Vec<usize>,
 		col_sequence: Vec<usize>,
@@ -121,10 +360,13 @@ impl Matrix {
 			col_sequence,
 			row_sequence,
 			rows,
+			pivot_strategy: PivotStrategy::default(),
+			tolerance: 0.0,
+			operations: Vec::new(),
 		}
 	}
 
-	pub fn rows(&self) -> &Vec<Row> {
+	pub fn rows(&self) -> &Vec<Row<T>> {
 		&self.rows
 	}
 
@@ -132,55 +374,176 @@ impl Matrix {
 		&self.state
 	}
 
-	fn null_row(&self, index: usize) -> Option<Matrix> {
-		let relevant_cell = self.rows[self.row_sequence[index]][self.col_sequence[index] as isize];
-		if relevant_cell == Fraction::ZERO {
-			return None;
-		}
-		let mut r = Matrix::new(
-			self.rows
-				.iter()
-				.enumerate()
-				.map(|(i, r)| {
-					if self.row_sequence.iter().position(|n| n == &i).unwrap() <= index {
-						r.clone()
-					} else {
-						r.clone() * relevant_cell
-							- self.rows[self.row_sequence[index]].clone()
-								* r[self.col_sequence[index] as isize]
-					}
-				})
-				.collect::<Vec<Row>>(),
-			if index + 2 == self.row_sequence.len() {
-				MatrixState::NormalizeRow(index + 1)
-			} else {
-				MatrixState::Null(index + 1)
-			},
-			self.row_sequence.clone(),
-			self.col_sequence.clone(),
-		);
-		r.optimize_indeces();
-		r.update_state();
-		Some(r)
+	/// The row operations which turned the previous step into this matrix, so step `i`
+	/// of a [`MatrixSolver`](crate::solver::MatrixSolver) carries its own in `solver[i]`.
+	/// Operations which didn't change a row are left out.
+	pub fn operations(&self) -> &Vec<RowOperation<T>> {
+		&self.operations
 	}
 
-	fn normalize_row(&self, index: usize) -> Option<Matrix> {
+	/// Swaps rows `a` and `b`, the elimination starts over with the new matrix.
+	pub fn swap_rows(&self, a: usize, b: usize) -> Self {
 		let mut new = self.clone();
-		let factor = new.rows[self.row_sequence[index]][self.col_sequence[index] as isize];
-		new.rows[self.row_sequence[index]] /= factor;
-		if new
-			.rows
-			.iter()
-			.any(|r| r.left().iter().all(|f| f == &Fraction::ZERO))
-		{
-			None
+		new.rows.swap(a, b);
+		new.restart();
+		new.operations = vec![RowOperation::Swap { a, b }];
+		new
+	}
+
+	/// Multiplies `row` by `factor`, the elimination starts over with the new matrix.
+	///
+	/// # Panics
+	/// Panics if `factor` is zero since that isn't reversible.
+	pub fn scale_row(&self, row: usize, factor: &T) -> Result<Self, Cell> {
+		assert!(!self.is_zero(factor), "can't scale a row by zero");
+		let mut new = self.clone();
+		new.rows[row] = new.rows[row]
+			.checked_mul(factor)
+			.map_err(|col| Cell { row, col })?;
+		new.restart();
+		new.operations = vec![RowOperation::Scale {
+			row,
+			factor: factor.clone(),
+		}];
+		Ok(new)
+	}
+
+	/// Adds `factor` times row `source` to row `target`,
+	/// the elimination starts over with the new matrix.
+	pub fn add_row_multiple(&self, target: usize, source: usize, factor: &T) -> Result<Self, Cell> {
+		let mut new = self.clone();
+		new.rows[target] = self.rows[source]
+			.checked_mul(&-factor.clone())
+			.and_then(|scaled| self.rows[target].checked_sub(&scaled))
+			.map_err(|col| Cell { row: target, col })?;
+		new.restart();
+		new.operations = vec![RowOperation::AddMultiple {
+			target,
+			source,
+			factor: factor.clone(),
+		}];
+		Ok(new)
+	}
+
+	/// Chooses all pivots again after the rows were changed by hand.
+	fn restart(&mut self) {
+		self.state = MatrixState::Initial;
+		self.optimize_indeces();
+		self.update_state();
+	}
+
+	fn null_row(&mut self, index: usize) -> Result<bool, Cell> {
+		let source = self.row_sequence[index];
+		let col = self.col_sequence[index] as isize;
+		if self.is_zero(&self.rows[source][col]) {
+			return Ok(false);
+		}
+		let mut targets = self.row_sequence[index + 1..].to_vec();
+		targets.sort_unstable();
+		let eliminated = targets
+			.into_iter()
+			.map(|i| {
+				self.eliminate(i, source, col)
+					.map(|(row, operation)| (i, row, operation))
+					.map_err(|col| Cell { row: i, col })
+			})
+			.collect::<Result<Vec<(usize, Row<T>, Option<RowOperation<T>>)>, Cell>>()?;
+		self.operations = Vec::with_capacity(eliminated.len());
+		for (i, row, operation) in eliminated {
+			self.rows[i] = row;
+			self.operations.extend(operation);
+		}
+		self.state = MatrixState::Null(index + 1);
+		self.optimize_indeces();
+		self.update_state();
+		Ok(true)
+	}
+
+	/// Eliminates the cell at `col` of row `target` using the pivot in row `source`,
+	/// with the operation used unless it didn't change the row.
+	fn eliminate(
+		&self,
+		target: usize,
+		source: usize,
+		col: isize,
+	) -> Result<(Row<T>, Option<RowOperation<T>>), isize> {
+		let (row, pivot_row) = (&self.rows[target], &self.rows[source]);
+		let pivot = &pivot_row[col];
+		match self.pivot_strategy {
+			PivotStrategy::LargestMagnitude => {
+				let factor = row[col].checked_div(pivot).ok_or(col)?;
+				let mut new = pivot_row
+					.checked_mul(&factor)
+					.and_then(|pivot_scaled| row.checked_sub(&pivot_scaled))?;
+				// avoid leaving rounding errors behind
+				new[col] = T::zero();
+				Ok((
+					new,
+					(!self.is_zero(&factor)).then(|| RowOperation::AddMultiple {
+						target,
+						source,
+						factor: -factor,
+					}),
+				))
+			}
+			_ => {
+				let new = row.checked_mul(pivot).and_then(|row_scaled| {
+					pivot_row
+						.checked_mul(&row[col])
+						.and_then(|pivot_scaled| row_scaled.checked_sub(&pivot_scaled))
+				})?;
+				Ok((
+					new,
+					self.combination(target, pivot.clone(), source, row[col].clone()),
+				))
+			}
+		}
+	}
+
+	/// `target ← target_factor · target − source_factor · source` as the simplest
+	/// [`RowOperation`], `None` if it doesn't change `target`.
+	fn combination(
+		&self,
+		target: usize,
+		target_factor: T,
+		source: usize,
+		source_factor: T,
+	) -> Option<RowOperation<T>> {
+		if !self.is_zero(&source_factor) {
+			Some(RowOperation::FractionFreeCombine {
+				target,
+				target_factor,
+				source,
+				source_factor,
+			})
+		} else if !self.is_one(&target_factor) {
+			Some(RowOperation::Scale {
+				row: target,
+				factor: target_factor,
+			})
 		} else {
-			new.update_state();
-			Some(new)
+			None
+		}
+	}
+
+	fn normalize_row(&mut self, index: usize) -> Result<bool, Cell> {
+		let row = self.row_sequence[index];
+		let col = self.col_sequence[index] as isize;
+		let factor = self.rows[row][col].clone();
+		if self.is_zero(&factor) {
+			return Ok(false);
 		}
+		let normalized = self.rows[row]
+			.checked_div(&factor)
+			.map_err(|col| Cell { row, col })?;
+		let scale = T::one().checked_div(&factor).ok_or(Cell { row, col })?;
+		self.rows[row] = normalized;
+		self.operations = vec![RowOperation::Scale { row, factor: scale }];
+		self.update_state();
+		Ok(true)
 	}
 
-	fn reinsert_row(&self, index: usize) -> Matrix {
+	fn reinsert_row(&mut self, index: usize) -> Result<bool, Cell> {
 		// assuming that the rows n + 1..
 		// are zeroed with only one `1` at `n`
 		//
@@ -188,13 +551,32 @@ impl Matrix {
 		// 0 1 0 | 0
 		// 0 0 1 | 2
 		// with n = 0
-		let mut new = self.clone();
-		for i in index..self.rows.len() - 1 {
-			new.rows[self.row_sequence[index]] = new.rows[self.row_sequence[index]].clone() * new.rows[self.row_sequence[i + 1]][self.col_sequence[i + 1] as isize] // should be 1 though
-				- new.rows[self.row_sequence[i + 1]].clone() * new.rows[self.row_sequence[index]][self.col_sequence[i + 1] as isize];
+		let row = self.row_sequence[index];
+		let mut reinserted = self.rows[row].clone();
+		let mut operations = Vec::new();
+		for i in index + 1..self.pivot_count() {
+			let source = self.row_sequence[i];
+			let other = &self.rows[source];
+			let col = self.col_sequence[i] as isize;
+			operations.extend(self.combination(
+				row,
+				other[col].clone(),
+				source,
+				reinserted[col].clone(),
+			));
+			reinserted = reinserted
+				.checked_mul(&other[col]) // should be 1 though
+				.and_then(|r| {
+					other
+						.checked_mul(&reinserted[col])
+						.and_then(|other| r.checked_sub(&other))
+				})
+				.map_err(|col| Cell { row, col })?;
 		}
-		new.update_state();
-		new
+		self.rows[row] = reinserted;
+		self.operations = operations;
+		self.update_state();
+		Ok(true)
 	}
 
 	pub fn update_state(&mut self) {
@@ -204,36 +586,40 @@ impl Matrix {
 				self.update_state();
 			}
 			MatrixState::Null(_) => {
+				// the first pivot which still has non-zero cells below it. Taking the first row
+				// with a non-zero cell instead isn't enough, a row which is already zero at that
+				// pivot can come before it, e.g. with `PivotStrategy::LargestMagnitude`.
 				if let Some(n) = self
 					.row_sequence
 					.iter()
 					.enumerate()
-					.map(|(n, i)| (n, &self.rows[*i]))
-					.position(|(n, r)| {
-						self.col_sequence[..n]
+					.filter_map(|(n, i)| {
+						self.col_sequence[..n.min(self.col_sequence.len())]
 							.iter()
-							.any(|i| r.left()[*i] != Fraction::ZERO)
-					}) {
-					self.state = MatrixState::Null(n - 1);
+							.position(|c| !self.is_zero(&self.rows[*i].left()[*c]))
+					})
+					.min()
+				{
+					self.state = MatrixState::Null(n);
 				} else {
-					self.state = MatrixState::NormalizeRow(self.rows.len() - 1);
+					self.state = MatrixState::NormalizeRow(0);
 					self.update_state()
 				}
 			}
 			MatrixState::NormalizeRow(_) | MatrixState::ReInsertRow(_) => {
-				self.state = self
-					.row_sequence
+				let pivots = self.pivot_count();
+				self.state = self.row_sequence[..pivots]
 					.iter()
 					.enumerate()
 					.map(|(n, i)| (n, &self.rows[*i]))
 					.rev()
 					.find_map(|(n, r)| {
-						if self.col_sequence[n + 1..]
+						if self.col_sequence[n + 1..pivots]
 							.iter()
-							.any(|i| r.left()[*i] != Fraction::ZERO)
+							.any(|i| !self.is_zero(&r.left()[*i]))
 						{
 							Some(MatrixState::ReInsertRow(n))
-						} else if r.left()[self.col_sequence[n]] != Fraction::ONE {
+						} else if !self.is_one(&r.left()[self.col_sequence[n]]) {
 							Some(MatrixState::NormalizeRow(n))
 						} else {
 							None
@@ -245,16 +631,199 @@ impl Matrix {
 		}
 	}
 
-	pub fn calculate_next(&self) -> Option<Matrix> {
+	/// Calculates the next step.
+	///
+	/// # Panics
+	/// Panics if a cell overflows, see [`Matrix::try_calculate_next`] for a fallible version.
+	pub fn calculate_next(&self) -> Option<Self> {
+		self.try_calculate_next()
+			.unwrap_or_else(|Cell { row, col }| {
+				panic!(
+					"Overflow in row {} at index {col} while {}.",
+					row + 1,
+					self.state
+				)
+			})
+	}
+
+	/// Calculates the next step, returning the cell which overflowed on failure.
+	pub fn try_calculate_next(&self) -> Result<Option<Self>, Cell> {
+		let mut next = self.clone();
+		Ok(next.try_step()?.then_some(next))
+	}
+
+	/// Calculates the next step in place without cloning the matrix,
+	/// `Ok(false)` if there is none. The matrix is unchanged if a cell overflows.
+	pub fn try_step(&mut self) -> Result<bool, Cell> {
 		match self.state {
 			MatrixState::Initial => panic!("Update state first!"),
 			MatrixState::Null(s) => self.null_row(s),
 			MatrixState::NormalizeRow(n) => self.normalize_row(n),
-			MatrixState::ReInsertRow(n) => Some(self.reinsert_row(n)),
-			MatrixState::Done => None,
+			MatrixState::ReInsertRow(n) => self.reinsert_row(n),
+			MatrixState::Done => Ok(false),
+		}
+	}
+
+	/// The general solution once the matrix is [`MatrixState::Done`],
+	/// `None` if it isn't done yet or the system is inconsistent.
+	pub fn general_solution(&self) -> Option<GeneralSolution<T>> {
+		if self.state != MatrixState::Done {
+			return None;
+		}
+		if self.inconsistent_row(None).is_some() {
+			return None;
+		}
+		Some(self.unchecked_general_solution())
+	}
+
+	/// The general solution, ignoring whether the system is inconsistent.
+	fn unchecked_general_solution(&self) -> GeneralSolution<T> {
+		let pivots = self.pivot_count();
+		let pivot_rows = &self.row_sequence[..pivots];
+		let width = self.width();
+		let mut particular =
+			vec![vec![T::zero(); width]; self.rows.first().map_or(0, |r| r.right().len())];
+		for (r, col) in pivot_rows.iter().zip(&self.col_sequence) {
+			for (c, f) in self.rows[*r].right().iter().enumerate() {
+				particular[c][*col] = f.clone();
+			}
+		}
+		let mut free_variables = self.col_sequence[pivots..].to_vec();
+		free_variables.sort_unstable();
+		let null_space = free_variables
+			.iter()
+			.map(|free| {
+				let mut v = vec![T::zero(); width];
+				v[*free] = T::one();
+				for (r, col) in pivot_rows.iter().zip(&self.col_sequence) {
+					v[*col] = -self.rows[*r].left()[*free].clone();
+				}
+				v
+			})
+			.collect();
+		GeneralSolution {
+			particular,
+			free_variables,
+			null_space,
+		}
+	}
+
+	/// Whether the system has one, infinitely many or no solution,
+	/// `None` if the matrix isn't [`MatrixState::Done`] yet.
+	pub fn outcome(&self) -> Option<SolveOutcome<T>> {
+		if self.state != MatrixState::Done {
+			None
+		} else if let Some(row) = self.inconsistent_row(None) {
+			Some(SolveOutcome::Inconsistent { row })
+		} else {
+			self.general_solution().map(SolveOutcome::from)
 		}
 	}
 
+	/// Like [`Matrix::outcome`], but every right side is an independent system
+	/// with one outcome each, so one of them can be inconsistent while the others aren't.
+	pub fn outcomes(&self) -> Option<Vec<SolveOutcome<T>>> {
+		if self.state != MatrixState::Done {
+			return None;
+		}
+		let general = self.unchecked_general_solution();
+		Some(
+			general
+				.particular
+				.iter()
+				.enumerate()
+				.map(|(c, particular)| match self.inconsistent_row(Some(c)) {
+					Some(row) => SolveOutcome::Inconsistent { row },
+					None => GeneralSolution {
+						particular: vec![particular.clone()],
+						free_variables: general.free_variables.clone(),
+						null_space: general.null_space.clone(),
+					}
+					.into(),
+				})
+				.collect(),
+		)
+	}
+
+	/// The left side in reduced row echelon form, with no right sides.
+	///
+	/// # Panics
+	/// If a cell overflows, see [`Matrix::calculate_next`].
+	fn reduced(&self) -> Self {
+		crate::solve(
+			self.with_rows(
+				self.rows
+					.iter()
+					.map(|r| Row::new(r.left().clone(), Vec::new()))
+					.collect(),
+			),
+		)
+	}
+
+	/// The amount of linearly independent rows or cols of the left side.
+	///
+	/// # Panics
+	/// If a cell overflows while reducing the left side, see [`Matrix::calculate_next`].
+	pub fn rank(&self) -> usize {
+		self.reduced().pivot_count()
+	}
+
+	/// A basis of the solutions of `A·x = 0` for the left side `A`,
+	/// one vector per free variable like [`GeneralSolution::null_space`].
+	///
+	/// # Panics
+	/// If a cell overflows while reducing the left side, see [`Matrix::calculate_next`].
+	pub fn null_space_basis(&self) -> Vec<Vec<T>> {
+		self.reduced().unchecked_general_solution().null_space
+	}
+
+	/// The cols of the left side which have a pivot, ascending.
+	///
+	/// # Panics
+	/// If a cell overflows while reducing the left side, see [`Matrix::calculate_next`].
+	pub fn column_space_basis(&self) -> Vec<Vec<T>> {
+		let reduced = self.reduced();
+		let mut cols = reduced.col_sequence[..reduced.pivot_count()].to_vec();
+		cols.sort_unstable();
+		cols.iter()
+			.map(|c| self.rows.iter().map(|r| r.left()[*c].clone()).collect())
+			.collect()
+	}
+
+	/// The non-zero rows of the reduced row echelon form of the left side,
+	/// ordered by the col of their pivot.
+	///
+	/// # Panics
+	/// If a cell overflows while reducing the left side, see [`Matrix::calculate_next`].
+	pub fn row_space_basis(&self) -> Vec<Vec<T>> {
+		let reduced = self.reduced();
+		let pivots = reduced.pivot_count();
+		let mut rows = reduced.col_sequence[..pivots]
+			.iter()
+			.zip(&reduced.row_sequence[..pivots])
+			.collect::<Vec<(&usize, &usize)>>();
+		rows.sort_unstable();
+		rows.iter()
+			.map(|(_, r)| reduced.rows[**r].left().clone())
+			.collect()
+	}
+
+	/// The first row which is zero on the left but not on the right side,
+	/// only looking at right side `right` if it is given.
+	fn inconsistent_row(&self, right: Option<usize>) -> Option<usize> {
+		self.row_sequence[self.pivot_count()..]
+			.iter()
+			.copied()
+			.filter(|r| {
+				self.rows[*r]
+					.right()
+					.iter()
+					.enumerate()
+					.any(|(c, f)| right.is_none_or(|right| right == c) && !self.is_zero(f))
+			})
+			.min()
+	}
+
 	pub fn row_sequence(&self) -> &Vec<usize> {
 		&self.row_sequence
 	}
@@ -262,18 +831,221 @@ impl Matrix {
 	pub fn col_sequence(&self) -> &Vec<usize> {
 		&self.col_sequence
 	}
+
+	/// Like [`Matrix::try_determinant`].
+	///
+	/// # Panics
+	/// If the left side isn't square or a cell overflows.
+	pub fn determinant(&self) -> T {
+		self.try_determinant().unwrap_or_else(|e| panic!("{e}"))
+	}
+
+	/// The determinant of the square left side. It is eliminated like in [`Matrix::try_step`],
+	/// undoing how every [`RowOperation`] (e.g. the pivot multiplication of a fraction-free
+	/// combination) scaled the rows, and the permutation of the pivots at the end.
+	pub fn try_determinant(&self) -> Result<T, DeterminantError> {
+		if self.rows.len() != self.width() {
+			return Err(DeterminantError::NotSquare);
+		}
+		let mut m = self.with_rows(self.rows.clone());
+		// what every row of `m` was multiplied with, the determinant of `self` is the one of `m`
+		// divided by all of them
+		let mut factors = vec![T::one(); m.rows.len()];
+		let mut swapped = false;
+		loop {
+			let col = match m.state {
+				MatrixState::Null(k)
+				| MatrixState::NormalizeRow(k)
+				| MatrixState::ReInsertRow(k) => m.col_sequence.get(k).map_or(0, |c| *c as isize),
+				MatrixState::Initial | MatrixState::Done => 0,
+			};
+			if !m.try_step()? {
+				break;
+			}
+			for operation in &m.operations {
+				let (row, factor) = match operation {
+					RowOperation::Scale { row, factor } => (*row, factor),
+					RowOperation::Swap { a, b } => {
+						factors.swap(*a, *b);
+						swapped = !swapped;
+						continue;
+					}
+					RowOperation::AddMultiple { .. } => continue,
+					RowOperation::FractionFreeCombine {
+						target,
+						target_factor,
+						..
+					} => (*target, target_factor),
+				};
+				factors[row] = factors[row].checked_mul(factor).ok_or(Cell { row, col })?;
+			}
+		}
+		if m.state != MatrixState::Done || m.pivot_count() < m.rows.len() {
+			return Ok(T::zero());
+		}
+		// the left side is a permutation matrix with the pivots instead of ones. Dividing every
+		// pivot by its row's factor in the order of the pivots keeps the product at the leading
+		// minors of `self` instead of letting it grow with the factors.
+		let mut determinant = T::one();
+		for (row, col) in m.row_sequence.iter().zip(&m.col_sequence) {
+			let col = *col as isize;
+			determinant = m.rows[*row][col]
+				.checked_div(&factors[*row])
+				.and_then(|pivot| determinant.checked_mul(&pivot))
+				.ok_or(Cell { row: *row, col })?;
+		}
+		if swapped != (is_odd_permutation(&m.row_sequence) != is_odd_permutation(&m.col_sequence)) {
+			determinant = T::zero()
+				.checked_sub(&determinant)
+				.ok_or(Cell { row: 0, col: 0 })?;
+		}
+		Ok(determinant)
+	}
+
+	/// The inverse of the square left side, calculated by solving it with the
+	/// identity as right sides. The right sides of the result are empty.
+	pub fn inverse(&self) -> Result<Self, InverseError> {
+		if self.rows.len() != self.width() {
+			return Err(InverseError::NotSquare);
+		}
+		let n = self.rows.len();
+		let identity = self
+			.rows
+			.iter()
+			.enumerate()
+			.map(|(i, r)| {
+				let mut right = vec![T::zero(); n];
+				right[i] = T::one();
+				Row::new(r.left().clone(), right)
+			})
+			.collect();
+		let solution = crate::try_solve(self.with_rows(identity))
+			.map_err(InverseError::Overflow)?
+			.general_solution()
+			.filter(GeneralSolution::is_unique)
+			.ok_or(InverseError::Singular)?;
+		// the `i`th right side is the `i`th column of the inverse
+		Ok(self.with_rows(
+			(0..n)
+				.map(|r| {
+					Row::new(
+						solution.particular.iter().map(|c| c[r].clone()).collect(),
+						Vec::new(),
+					)
+				})
+				.collect(),
+		))
+	}
+
+	/// The factorization `P·A = L·U` of the square left side, see [`LuDecomposition`].
+	pub fn lu(&self) -> Result<LuDecomposition<T>, LuError> {
+		LuDecomposition::new(self)
+	}
+
+	/// Like [`Matrix::try_bareiss_determinant`].
+	///
+	/// # Panics
+	/// If the left side isn't square or a cell overflows.
+	pub fn bareiss_determinant(&self) -> T {
+		self.try_bareiss_determinant()
+			.unwrap_or_else(|e| panic!("{e}"))
+	}
+
+	/// The determinant of the square left side using the Bareiss algorithm.
+	/// Every division is exact, so integer cells stay integers throughout.
+	pub fn try_bareiss_determinant(&self) -> Result<T, DeterminantError> {
+		if self.rows.len() != self.width() {
+			return Err(DeterminantError::NotSquare);
+		}
+		let n = self.rows.len();
+		let mut rows = self
+			.rows
+			.iter()
+			.map(|r| r.left().clone())
+			.collect::<Vec<Vec<T>>>();
+		let mut order = (0..n).collect::<Vec<usize>>();
+		let mut previous = T::one();
+		for k in 0..n {
+			let Some(pivot) = (k..n).find(|i| !self.is_zero(&rows[*i][k])) else {
+				return Ok(T::zero());
+			};
+			if pivot != k {
+				rows.swap(pivot, k);
+				order.swap(pivot, k);
+			}
+			for i in k + 1..n {
+				for j in k + 1..n {
+					rows[i][j] = rows[i][j]
+						.checked_mul(&rows[k][k])
+						.and_then(|a| {
+							rows[i][k]
+								.checked_mul(&rows[k][j])
+								.and_then(|b| a.checked_sub(&b))
+						})
+						.and_then(|c| c.checked_div(&previous))
+						.ok_or(Cell {
+							row: order[i],
+							col: j as isize,
+						})?;
+				}
+			}
+			previous = rows[k][k].clone();
+		}
+		if is_odd_permutation(&order) {
+			Ok(T::zero()
+				.checked_sub(&previous)
+				.ok_or(Cell { row: 0, col: 0 })?)
+		} else {
+			Ok(previous)
+		}
+	}
+
+	/// A new matrix with the pivot strategy and tolerance of this one.
+	fn with_rows(&self, rows: Vec<Row<T>>) -> Self {
+		let mut new = Self::new_with_rows(rows);
+		new.pivot_strategy = self.pivot_strategy;
+		new.tolerance = self.tolerance;
+		new.optimize_indeces();
+		new.update_state();
+		new
+	}
+
+	/// The augmented matrix as a LaTeX `array` in parentheses,
+	/// with a vertical bar between the left and the right side.
+	pub fn to_latex(&self) -> String {
+		let (left, right) = self
+			.rows
+			.first()
+			.map_or((0, 0), |r| (r.left().len(), r.right().len()));
+		format!(
+			"\\left(\\begin{{array}}{{{}|{}}}\n{}\n\\end{{array}}\\right)",
+			"c".repeat(left),
+			"c".repeat(right),
+			self.rows
+				.iter()
+				.map(|r| r
+					.left()
+					.iter()
+					.chain(r.right())
+					.map(Scalar::to_latex)
+					.collect::<Vec<String>>()
+					.join(" & "))
+				.collect::<Vec<String>>()
+				.join(" \\\\\n")
+		)
+	}
 }
-impl PartialEq for Matrix {
+impl<T: Scalar> PartialEq for Matrix<T> {
 	fn eq(&self, other: &Self) -> bool {
 		self.rows == other.rows
 	}
 }
-impl Debug for Matrix {
+impl<T: Scalar> Debug for Matrix<T> {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		writeln!(f, "\n{}", self)
 	}
 }
-impl Display for Matrix {
+impl<T: Scalar> Display for Matrix<T> {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		let longest = self
 			.rows
@@ -303,14 +1075,254 @@ impl Display for Matrix {
 		)
 	}
 }
-fn pad_row(v: &[Fraction], l: usize, s: &str) -> String {
+/// Whether `p` has an odd number of inversions.
+pub(crate) fn is_odd_permutation(p: &[usize]) -> bool {
+	(0..p.len())
+		.map(|i| p[i + 1..].iter().filter(|j| p[i] > **j).count())
+		.sum::<usize>()
+		% 2 == 1
+}
+fn pad_row<T: Scalar>(v: &[T], l: usize, s: &str) -> String {
 	v.iter()
 		.map(|f| format!("{f:>l$}"))
 		.collect::<Vec<String>>()
 		.join(s)
 }
 
+/// An elementary row operation, rows are indexed like [`Matrix::rows`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RowOperation<T = Fraction> {
+	/// `row ← factor · row`
+	Scale { row: usize, factor: T },
+	/// `a ↔ b`
+	Swap { a: usize, b: usize },
+	/// `target ← target + factor · source`
+	AddMultiple {
+		target: usize,
+		source: usize,
+		factor: T,
+	},
+	/// `target ← target_factor · target − source_factor · source`,
+	/// used to eliminate without dividing.
+	FractionFreeCombine {
+		target: usize,
+		target_factor: T,
+		source: usize,
+		source_factor: T,
+	},
+}
+impl<T: Scalar> Display for RowOperation<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		// `− factor` with the sign of `factor` folded in
+		let minus = |factor: &T| {
+			format!(
+				"{} {}",
+				if factor.is_negative() { "+" } else { "−" },
+				factor.abs()
+			)
+		};
+		match self {
+			RowOperation::Scale { row, factor } => write!(f, "R{0} ← {factor}·R{0}", row + 1),
+			RowOperation::Swap { a, b } => write!(f, "R{} ↔ R{}", a + 1, b + 1),
+			RowOperation::AddMultiple {
+				target,
+				source,
+				factor,
+			} => write!(
+				f,
+				"R{0} ← R{0} {1}·R{2}",
+				target + 1,
+				minus(&-factor.clone()),
+				source + 1
+			),
+			RowOperation::FractionFreeCombine {
+				target,
+				target_factor,
+				source,
+				source_factor,
+			} => write!(
+				f,
+				"R{0} ← {1}·R{0} {2}·R{3}",
+				target + 1,
+				target_factor,
+				minus(source_factor),
+				source + 1
+			),
+		}
+	}
+}
+
+/// A cell of a [`Matrix`], `col` is indexed like [`Row`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cell {
+	pub row: usize,
+	pub col: isize,
+}
+
+/// Why the determinant of a [`Matrix`] couldn't be calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeterminantError {
+	/// The left side has more or less rows than cols.
+	NotSquare,
+	/// The cell overflowed.
+	Overflow(Cell),
+}
+impl From<Cell> for DeterminantError {
+	fn from(cell: Cell) -> Self {
+		DeterminantError::Overflow(cell)
+	}
+}
+impl Display for DeterminantError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			DeterminantError::NotSquare => write!(f, "The left side has to be square."),
+			DeterminantError::Overflow(Cell { row, col }) => write!(
+				f,
+				"Overflow in row {} at index {col} while calculating the determinant.",
+				row + 1
+			),
+		}
+	}
+}
+impl Error for DeterminantError {}
+
+/// Why the inverse of a [`Matrix`] couldn't be calculated.
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InverseError {
+	/// The left side has more or less rows than cols.
+	NotSquare,
+	/// The left side has no inverse.
+	Singular,
+	/// A cell overflowed while solving the left side with the identity as right sides.
+	Overflow(ArithmeticOverflow),
+}
+impl Display for InverseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			InverseError::NotSquare => write!(f, "The left side has to be square."),
+			InverseError::Singular => write!(f, "The matrix is singular."),
+			InverseError::Overflow(e) => write!(f, "{e}"),
+		}
+	}
+}
+impl Error for InverseError {}
+
+/// Why a deserialized [`Matrix`] was rejected.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidMatrix {
+	/// The row has another amount of elements on the left or right side than the first row.
+	RowWidth(usize),
+	/// The row sequence isn't a permutation of the rows.
+	RowSequence,
+	/// The col sequence isn't a permutation of the cols.
+	ColSequence,
+	/// The tolerance is negative or not finite.
+	Tolerance,
+	/// The state can't be continued, e.g. it points at a pivot which doesn't exist or is zero.
+	State(MatrixState),
+}
+#[cfg(feature = "serde")]
+impl Display for InvalidMatrix {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			InvalidMatrix::RowWidth(row) => write!(
+				f,
+				"Row {} needs as many elements on each side as the first row.",
+				row + 1
+			),
+			InvalidMatrix::RowSequence => {
+				write!(f, "The row sequence has to contain every row once.")
+			}
+			InvalidMatrix::ColSequence => {
+				write!(f, "The col sequence has to contain every col once.")
+			}
+			InvalidMatrix::Tolerance => {
+				write!(f, "The tolerance has to be finite and at least 0.")
+			}
+			InvalidMatrix::State(state) => write!(f, "Can't continue from `{state}`."),
+		}
+	}
+}
+#[cfg(feature = "serde")]
+impl Error for InvalidMatrix {}
+
+/// The fields of a [`Matrix`] before they are checked, see [`InvalidMatrix`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawMatrix<T> {
+	rows: Vec<Row<T>>,
+	state: MatrixState,
+	row_sequence: Vec<usize>,
+	col_sequence: Vec<usize>,
+	pivot_strategy: PivotStrategy,
+	tolerance: f64,
+	operations: Vec<RowOperation<T>>,
+}
+#[cfg(feature = "serde")]
+impl<T: Scalar> TryFrom<RawMatrix<T>> for Matrix<T> {
+	type Error = InvalidMatrix;
+
+	fn try_from(raw: RawMatrix<T>) -> Result<Self, Self::Error> {
+		let is_permutation = |sequence: &[usize], len: usize| {
+			let mut sorted = sequence.to_vec();
+			sorted.sort_unstable();
+			sorted.into_iter().eq(0..len)
+		};
+		let width = raw
+			.rows
+			.first()
+			.map_or((0, 0), |r| (r.left().len(), r.right().len()));
+		if let Some(row) = raw
+			.rows
+			.iter()
+			.position(|r| (r.left().len(), r.right().len()) != width)
+		{
+			return Err(InvalidMatrix::RowWidth(row));
+		}
+		if !is_permutation(&raw.row_sequence, raw.rows.len()) {
+			return Err(InvalidMatrix::RowSequence);
+		}
+		if !is_permutation(&raw.col_sequence, width.0) {
+			return Err(InvalidMatrix::ColSequence);
+		}
+		if !raw.tolerance.is_finite() || raw.tolerance < 0.0 {
+			return Err(InvalidMatrix::Tolerance);
+		}
+		let m = Self {
+			rows: raw.rows,
+			state: raw.state,
+			row_sequence: raw.row_sequence,
+			col_sequence: raw.col_sequence,
+			pivot_strategy: raw.pivot_strategy,
+			tolerance: raw.tolerance,
+			operations: raw.operations,
+		};
+		// every step indexes both sequences with its pivot, which has to be non-zero to make
+		// progress. Once the cols are nulled, every non-zero row needs a pivot.
+		let pivots = match m.state {
+			MatrixState::Null(_) => m.rows.len().min(width.0),
+			_ => m.pivot_count(),
+		};
+		match m.state {
+			MatrixState::Initial => return Err(InvalidMatrix::State(m.state)),
+			MatrixState::Null(n) | MatrixState::NormalizeRow(n) | MatrixState::ReInsertRow(n)
+				if n >= pivots
+					|| pivots > width.0
+					|| m.is_zero(&m.rows[m.row_sequence[n]].left()[m.col_sequence[n]]) =>
+			{
+				return Err(InvalidMatrix::State(m.state))
+			}
+			_ => {}
+		}
+		Ok(m)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MatrixState {
 	Initial,
 	Null(usize),
@@ -331,7 +1343,8 @@ impl Display for MatrixState {
 }
 
 /// A macro to easily create matrices.
-/// The amount of columns on the left side must match the amount of rows.
+/// Every row needs the same amount of columns on each side.
+/// The cells are [`Fraction`]s unless another [`Scalar`] is given in front.
 ///
 /// # Example
 /// ```
@@ -353,17 +1366,35 @@ impl Display for MatrixState {
 ///         ),
 ///     ])
 /// );
+/// assert_eq!(
+///     matrix![f64; [1; 2] | [3], [4; 5] | [6]].rows()[1].left(),
+///     &vec![4.0, 5.0]
+/// );
 /// ```
 #[macro_export]
 macro_rules! matrix {
     ($([$($left:expr);+] | [$($right:expr);+]),+ $(,)?) => {
-		$crate::matrix::Matrix::create([ $([$($left.into()),+]),+ ], [ $([$($right.into()),+]),+ ])
+		<$crate::matrix::Matrix>::create([ $([$($left.into()),+]),+ ], [ $([$($right.into()),+]),+ ])
+    };
+    ($t:ty; $([$($left:expr);+] | [$($right:expr);+]),+ $(,)?) => {
+		<$crate::matrix::Matrix<$t>>::create([ $([$($left.into()),+]),+ ], [ $([$($right.into()),+]),+ ])
     };
 }
 
 #[cfg(test)]
 mod test {
-	use crate::{matrix::MatrixState, solve, solve_with_history};
+	use crate::{
+		big_fraction::BigFraction,
+		fraction::Fraction,
+		invert,
+		matrix::{Cell, DeterminantError, InverseError, Matrix, MatrixState, PivotStrategy},
+		row::Row,
+		scalar::Scalar,
+		solution::SolveOutcome,
+		solve, solve_float, solve_with_history,
+		solver::MatrixSolver,
+		try_solve,
+	};
 
 	#[cfg(test)]
 	mod optimize {
@@ -547,4 +1578,561 @@ mod test {
 		assert_eq!(solve(initial.clone()), step7);
 		assert_eq!(solve_with_history(initial).get(-1), step7);
 	}
+
+	#[test]
+	fn overflow() {
+		let cut = matrix![[2; 1] | [1], [1; usize::MAX] | [1]];
+		let err = try_solve(cut.clone()).unwrap_err();
+		assert_eq!(err.step, 1);
+		assert_eq!(err.state, MatrixState::Null(0));
+		assert_eq!(err.cell, Cell { row: 1, col: 1 });
+		assert!(cut.try_calculate_next().is_err());
+
+		assert_eq!(
+			try_solve(matrix![[1; 1] | [2], [1; -1] | [0]]),
+			Ok(matrix![[1; 0] | [1], [0; 1] | [1]])
+		);
+	}
+
+	#[test]
+	fn determinant() {
+		let cut = matrix![[2; 1] | [3], [1; 3] | [4]];
+		assert_eq!(cut.determinant(), Fraction::from(5));
+		assert_eq!(cut.bareiss_determinant(), Fraction::from(5));
+
+		let cut = matrix![[0; 2; 1] | [1], [1; 1; 0] | [1], [3; 0; 1] | [1]];
+		assert_eq!(cut.determinant(), Fraction::from(-5));
+		assert_eq!(cut.bareiss_determinant(), Fraction::from(-5));
+		let float = matrix![f64; [0; 2; 1] | [1], [1; 1; 0] | [1], [3; 0; 1] | [1]]
+			.with_pivot_strategy(PivotStrategy::LargestMagnitude);
+		assert!((float.determinant() + 5.0).abs() < 1e-9);
+
+		let singular = matrix![[1; 2; 3] | [1], [2; 4; 6] | [1], [0; 1; 1] | [1]];
+		assert_eq!(singular.determinant(), Fraction::ZERO);
+		assert_eq!(singular.bareiss_determinant(), Fraction::ZERO);
+
+		let rectangular = matrix![[1; 1; 1] | [6], [1; 0; -1] | [-2]];
+		assert_eq!(
+			rectangular.try_determinant(),
+			Err(DeterminantError::NotSquare)
+		);
+		assert_eq!(
+			rectangular.try_bareiss_determinant(),
+			Err(DeterminantError::NotSquare)
+		);
+		let huge = matrix![[i64::MAX; 1] | [1], [1; i64::MAX] | [1]];
+		assert!(matches!(
+			huge.try_bareiss_determinant(),
+			Err(DeterminantError::Overflow(_))
+		));
+
+		// product of `j - i` for `1 <= i < j <= 5`
+		assert_eq!(
+			vandermonde::<BigFraction>(5).determinant(),
+			BigFraction::from(288)
+		);
+		assert_eq!(
+			vandermonde::<Fraction>(5).bareiss_determinant(),
+			Fraction::from(288)
+		);
+		assert_eq!(
+			solve(vandermonde::<BigFraction>(5)).determinant(),
+			BigFraction::from(1)
+		);
+
+		// dividing by every row factor only at the end overflowed here
+		let dense = matrix![
+			[3; -3; 0; -1; 3; 1] | [1],
+			[1; -2; 2; 2; -3; -1] | [1],
+			[-3; -2; 1; 2; 0; 2] | [1],
+			[0; 3; 1; -3; 3; 1] | [1],
+			[2; 3; 2; -2; -2; 2] | [1],
+			[0; -3; -1; -1; -1; -3] | [1]
+		];
+		assert_eq!(dense.try_determinant(), Ok(Fraction::from(-3516)));
+		assert_eq!(dense.determinant(), dense.bareiss_determinant());
+	}
+
+	#[test]
+	fn inverse() {
+		let cut = matrix![[0; 2; 1] | [1], [1; 1; 0] | [1], [3; 0; 1] | [1]];
+		let expected: Matrix = Matrix::create_with_rows(vec![
+			Row::new(
+				vec![
+					Fraction::negative(1, 5),
+					Fraction::positive(2, 5),
+					Fraction::positive(1, 5),
+				],
+				vec![],
+			),
+			Row::new(
+				vec![
+					Fraction::positive(1, 5),
+					Fraction::positive(3, 5),
+					Fraction::negative(1, 5),
+				],
+				vec![],
+			),
+			Row::new(
+				vec![
+					Fraction::positive(3, 5),
+					Fraction::negative(6, 5),
+					Fraction::positive(2, 5),
+				],
+				vec![],
+			),
+		]);
+		assert_eq!(cut.inverse(), Ok(expected.clone()));
+		assert_eq!(invert(cut), Ok(expected));
+		assert_eq!(
+			matrix![[1; 2] | [1], [2; 4] | [1]].inverse(),
+			Err(InverseError::Singular)
+		);
+		assert_eq!(
+			matrix![[1; 1; 1] | [6], [1; 0; -1] | [-2]].inverse(),
+			Err(InverseError::NotSquare)
+		);
+		assert!(matches!(
+			invert(matrix![[i64::MAX; 1] | [1], [1; i64::MAX] | [1]]),
+			Err(InverseError::Overflow(_))
+		));
+		let inverse = vandermonde::<BigFraction>(4).inverse().unwrap();
+		for (a, b) in inverse
+			.inverse()
+			.unwrap()
+			.rows()
+			.iter()
+			.zip(vandermonde::<BigFraction>(4).rows())
+		{
+			assert_eq!(a.left(), b.left());
+		}
+	}
+
+	/// Whether `outcome` solves the system `m`.
+	fn satisfies(m: &Matrix, outcome: &SolveOutcome) -> bool {
+		let product = |v: &Vec<Fraction>, r: &Row| {
+			r.left()
+				.iter()
+				.zip(v)
+				.fold(Fraction::ZERO, |s, (a, b)| s + *a * *b)
+		};
+		let Some(solution) = outcome.clone().general_solution() else {
+			return true;
+		};
+		m.rows().iter().all(|r| {
+			solution
+				.particular
+				.iter()
+				.enumerate()
+				.all(|(c, p)| product(p, r) == r.right()[c])
+				&& solution
+					.null_space
+					.iter()
+					.all(|n| product(n, r) == Fraction::ZERO)
+		})
+	}
+
+	#[test]
+	fn pivot_strategies() {
+		let strategies = [
+			PivotStrategy::Natural,
+			PivotStrategy::MaxZeros,
+			PivotStrategy::GreedyMaxZeros,
+			PivotStrategy::SmallestEntry,
+			PivotStrategy::Markowitz,
+			PivotStrategy::LargestMagnitude,
+		];
+		let systems = [
+			matrix![[0; 2; 1] | [1], [1; 1; 0] | [1], [3; 0; 1] | [1]],
+			matrix![[1; 2; 1] | [(1, 2); 1], [2; 4; 0] | [0; 0]],
+			matrix![[0; 1; 2] | [1], [0; 2; 4] | [2], [0; 0; 0] | [0]],
+			matrix![[1; 1] | [1], [1; 1] | [2]],
+		];
+		for m in systems {
+			let expected = solve(m.clone()).outcome().unwrap();
+			for strategy in strategies {
+				let outcome = solve(m.clone().with_pivot_strategy(strategy))
+					.outcome()
+					.unwrap();
+				assert_eq!(
+					std::mem::discriminant(&outcome),
+					std::mem::discriminant(&expected),
+					"{strategy:?}"
+				);
+				assert!(satisfies(&m, &outcome), "{strategy:?}");
+			}
+		}
+
+		// the default order could already be an echelon form which can't be reordered
+		let unordered = |m: Matrix| Matrix::new_with_rows(m.rows().clone());
+		let natural = unordered(matrix![[0; 1] | [1], [1; 1] | [1]])
+			.with_pivot_strategy(PivotStrategy::Natural);
+		assert_eq!(
+			(natural.row_sequence(), natural.col_sequence()),
+			(&vec![1, 0], &vec![0, 1])
+		);
+		let markowitz = unordered(matrix![[1; 1] | [1], [1; 0] | [1]])
+			.with_pivot_strategy(PivotStrategy::Markowitz);
+		assert_eq!(
+			(markowitz.row_sequence(), markowitz.col_sequence()),
+			(&vec![1, 0], &vec![0, 1])
+		);
+		let smallest = unordered(matrix![[3; 1] | [1], [2; 5] | [1]])
+			.with_pivot_strategy(PivotStrategy::SmallestEntry);
+		assert_eq!(
+			(smallest.row_sequence(), smallest.col_sequence()),
+			(&vec![0, 1], &vec![1, 0])
+		);
+
+		// x_i + x_{i + 1} = 1 and x_n = 1, too wide to try every column order
+		let n = 16;
+		let chain: Matrix = Matrix::create_with_rows(
+			(0..n)
+				.map(|i| {
+					let mut left = vec![Fraction::ZERO; n];
+					left[i] = Fraction::ONE;
+					if i + 1 < n {
+						left[i + 1] = Fraction::ONE;
+					}
+					Row::new(left, vec![Fraction::ONE])
+				})
+				.collect(),
+		);
+		let mut solver =
+			MatrixSolver::new(chain.clone()).with_pivot_strategy(PivotStrategy::GreedyMaxZeros);
+		let outcome = solver.solve().unwrap();
+		assert!(matches!(outcome, SolveOutcome::Unique { .. }));
+		assert!(satisfies(&chain, &outcome));
+
+		// dense, so creating it with the default strategy would try all 12! column orders
+		let n = 12;
+		let dense = (0..n)
+			.map(|i| {
+				let left = (0..n)
+					.map(|j| BigFraction::from(if i == j { n as i64 + 1 } else { 1 }))
+					.collect();
+				Row::new(left, vec![BigFraction::from(2 * n as i64)])
+			})
+			.collect::<Vec<Row<BigFraction>>>();
+		for strategy in [PivotStrategy::Natural, PivotStrategy::GreedyMaxZeros] {
+			let cut = Matrix::create_with_pivot_strategy(dense.clone(), strategy);
+			assert_eq!(cut.pivot_strategy(), strategy);
+			assert_eq!(
+				solve(cut).general_solution().unwrap().particular,
+				vec![vec![BigFraction::from(1); n]]
+			);
+		}
+	}
+
+	#[test]
+	fn subspaces() {
+		let cut = matrix![[1; 2; 3] | [1], [2; 4; 6] | [2], [1; 1; 1] | [0]];
+		assert_eq!(cut.rank(), 2);
+		let f = |v: &[i64]| {
+			v.iter()
+				.map(|i| Fraction::from(*i))
+				.collect::<Vec<Fraction>>()
+		};
+		assert_eq!(cut.null_space_basis(), vec![f(&[1, -2, 1])]);
+		assert_eq!(cut.column_space_basis(), vec![f(&[1, 2, 1]), f(&[2, 4, 1])]);
+		assert_eq!(cut.row_space_basis(), vec![f(&[1, 0, -1]), f(&[0, 1, 2])]);
+
+		let wide = matrix![[1; 2; 1; 0] | [1], [2; 4; 0; 1] | [0]];
+		assert_eq!(wide.rank(), 2);
+		let null_space = wide.null_space_basis();
+		assert_eq!(null_space.len(), 2);
+		for v in &null_space {
+			for r in wide.rows() {
+				assert_eq!(
+					r.left()
+						.iter()
+						.zip(v)
+						.fold(Fraction::ZERO, |s, (a, b)| s + *a * *b),
+					Fraction::ZERO
+				);
+			}
+		}
+		assert_eq!(wide.column_space_basis().len(), 2);
+
+		let zero = matrix![[0; 0] | [1], [0; 0] | [1]];
+		assert_eq!(zero.rank(), 0);
+		assert_eq!(zero.null_space_basis(), vec![f(&[1, 0]), f(&[0, 1])]);
+		assert!(zero.column_space_basis().is_empty());
+		assert!(zero.row_space_basis().is_empty());
+	}
+
+	fn vandermonde<T: Scalar + From<i64>>(n: usize) -> Matrix<T> {
+		Matrix::create_with_rows(
+			(1..=n as i64)
+				.map(|x| {
+					Row::new(
+						(0..n as u32).map(|j| x.pow(j).into()).collect(),
+						vec![x.into()],
+					)
+				})
+				.collect(),
+		)
+	}
+
+	#[test]
+	fn big_fraction() {
+		assert!(try_solve(vandermonde::<Fraction>(8)).is_err());
+		let solved = solve(vandermonde::<BigFraction>(8));
+		assert_eq!(solved.state(), &MatrixState::Done);
+		for (i, r) in solved.rows().iter().enumerate() {
+			let expected = if i == 1 { 1 } else { 0 };
+			assert_eq!(r.right(), &vec![BigFraction::from(expected)]);
+		}
+	}
+
+	#[test]
+	fn float() {
+		assert_eq!(
+			solve(matrix![f64; [1; 1] | [2], [1; -1] | [0]]),
+			matrix![f64; [1; 0] | [1], [0; 1] | [1]]
+		);
+	}
+
+	#[test]
+	fn partial_pivoting() {
+		let cut = matrix![f64; [1e-20; 1] | [1], [1; 1] | [2]];
+		let solved = solve(cut.clone());
+		assert_eq!(solved.rows()[0].right(), &vec![0.0]);
+
+		let solved = solve_float(cut.clone(), 1e-12);
+		assert_eq!(solved.state(), &MatrixState::Done);
+		assert_eq!(solved.row_sequence(), &vec![1, 0]);
+		assert!((solved.rows()[0].right()[0] - 1.0).abs() < 1e-12);
+		assert!((solved.rows()[1].right()[0] - 1.0).abs() < 1e-12);
+
+		let cut = cut.with_pivot_strategy(PivotStrategy::LargestMagnitude);
+		assert_eq!(cut.row_sequence(), &vec![1, 0]);
+		assert_eq!(cut.col_sequence(), &vec![0, 1]);
+
+		// the second row is already zero in the first col, but the third one isn't
+		let cut = matrix![f64; [0; 2; 1] | [1], [1; 1; 0] | [1], [3; 0; 1] | [1]]
+			.with_pivot_strategy(PivotStrategy::LargestMagnitude);
+		assert_eq!(cut.row_sequence(), &vec![2, 0, 1]);
+		assert_eq!(cut.state(), &MatrixState::Null(0));
+		let solved = solve_float(cut, 1e-12);
+		assert_eq!(solved.state(), &MatrixState::Done);
+		let x = solved.general_solution().unwrap().particular[0].clone();
+		for (x, expected) in x.iter().zip([0.4, 0.6, -0.2]) {
+			assert!((x - expected).abs() < 1e-12);
+		}
+	}
+
+	#[test]
+	fn tolerance() {
+		// singular up to rounding errors
+		let cut = matrix![f64; [0.1; 0.3] | [1], [1; 3] | [2]];
+		assert!(solve(cut.clone()).general_solution().unwrap().is_unique());
+		let solved = solve_float(cut, 1e-12);
+		assert_eq!(solved.state(), &MatrixState::Done);
+		assert_eq!(solved.general_solution(), None);
+	}
+
+	#[test]
+	fn rectangular() {
+		// x + y + z = 6, x - z = -2
+		let solved = solve(matrix![[1; 1; 1] | [6], [1; 0; -1] | [-2]]);
+		assert_eq!(solved.state(), &MatrixState::Done);
+		let solution = solved.general_solution().unwrap();
+		assert_eq!(solution.free_variables.len(), 1);
+		let (particular, direction) = (&solution.particular[0], &solution.null_space[0]);
+		for t in [0, 1, -3] {
+			let x = particular
+				.iter()
+				.zip(direction)
+				.map(|(p, d)| *p + *d * Fraction::from(t))
+				.collect::<Vec<Fraction>>();
+			assert_eq!(x[0] + x[1] + x[2], Fraction::from(6));
+			assert_eq!(x[0] - x[2], Fraction::from(-2));
+		}
+
+		// more equations than unknowns, the last one is redundant
+		let solved = solve(matrix![[1; 1] | [3], [1; -1] | [1], [2; 0] | [4]]);
+		assert_eq!(
+			solved.general_solution().unwrap().particular,
+			vec![vec![Fraction::from(2), Fraction::ONE]]
+		);
+		let solved = solve(matrix![[1; 1] | [3], [1; -1] | [1], [2; 0] | [5]]);
+		assert_eq!(solved.state(), &MatrixState::Done);
+		assert_eq!(solved.general_solution(), None);
+	}
+
+	#[test]
+	fn outcome() {
+		let mut solver = MatrixSolver::new(matrix![[1; 1] | [2], [1; -1] | [0]]);
+		assert_eq!(
+			solver.solve(),
+			Some(SolveOutcome::Unique {
+				solution: vec![vec![Fraction::ONE, Fraction::ONE]]
+			})
+		);
+		assert_eq!(
+			solve(matrix![[1; 1] | [1], [2; 2] | [2]]).outcome(),
+			Some(SolveOutcome::Infinite {
+				free_vars: vec![1],
+				particular: vec![vec![Fraction::ONE, Fraction::ZERO]],
+				null_space: vec![vec![Fraction::M_ONE, Fraction::ONE]],
+			})
+		);
+		assert_eq!(
+			solve(matrix![[1; 1] | [1], [1; 0] | [1], [2; 2] | [3]]).outcome(),
+			Some(SolveOutcome::Inconsistent { row: 2 })
+		);
+		let cut = matrix![[1; 1] | [1], [1; 1] | [2]];
+		assert_eq!(cut.outcome(), None);
+		assert_eq!(
+			solve_with_history(cut).to_string().lines().last(),
+			Some("No solution, equation 2 reduces to 0 = c with c ≠ 0.")
+		);
+	}
+
+	#[test]
+	fn to_latex() {
+		assert_eq!(
+			matrix![[(1, 2); -1] | [(-3, 4)], [0; 2] | [1]].to_latex(),
+			"\\left(\\begin{array}{cc|c}\n\\frac{1}{2} & -1 & -\\frac{3}{4} \\\\\n0 & 2 & 1\n\\end{array}\\right)"
+		);
+		let latex = solve_with_history(matrix![[1; 1] | [2], [1; -1] | [0]]).to_latex();
+		assert!(latex.starts_with("\\begin{gather*}\n\\left(\\begin{array}{cc|c}\n1 & 1 & 2"));
+		assert!(latex.contains(" \\\\\n\\xrightarrow{\\text{Nulling first 1 col(s)}} \\left("));
+		assert!(latex.ends_with(
+			"\\end{gather*}\n\\begin{align*}\nx_{1} &= 1 \\\\\nx_{2} &= 1\n\\end{align*}"
+		));
+		let latex =
+			solve_with_history(matrix![[1; 2; 1] | [(1, 2); 1], [2; 4; 0] | [0; 0]]).to_latex();
+		assert!(latex.ends_with(
+			"x_{3} &= \\frac{1}{2} + t \\\\\nx_{1} &= -2x_{2} \\\\\nx_{2} &\\text{ is free}\n\\end{align*}"
+		));
+	}
+
+	#[test]
+	fn rank_deficient() {
+		// the middle column is zero and the last row is a multiple of the first
+		let solved = solve(matrix![
+			[1; 0; 2] | [1],
+			[0; 0; 1] | [1],
+			[2; 0; 4] | [2]
+		]);
+		let solution = solved.general_solution().unwrap();
+		assert_eq!(solution.free_variables, vec![1]);
+		assert_eq!(
+			solution.particular,
+			vec![vec![Fraction::from(-1), Fraction::ZERO, Fraction::ONE]]
+		);
+		assert_eq!(
+			solution.null_space,
+			vec![vec![Fraction::ZERO, Fraction::ONE, Fraction::ZERO]]
+		);
+
+		// everything is free
+		let solution = solve(matrix![[0; 0] | [0], [0; 0] | [0]])
+			.general_solution()
+			.unwrap();
+		assert_eq!(solution.free_variables, vec![0, 1]);
+
+		let solution = solve_float(
+			matrix![f64; [1; 2; 3] | [1], [2; 4; 6] | [2], [1; 1; 1] | [0]],
+			1e-12,
+		)
+		.general_solution()
+		.unwrap();
+		assert_eq!(solution.free_variables, vec![2]);
+		assert!((solution.null_space[0][0] - 1.0).abs() < 1e-12);
+		assert!((solution.null_space[0][1] + 2.0).abs() < 1e-12);
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn serde_validation() {
+		use crate::matrix::{InvalidMatrix, RawMatrix};
+
+		let with_tolerance = |rows: &str,
+		                      state: &str,
+		                      row_sequence: &str,
+		                      col_sequence: &str,
+		                      tolerance: f64| {
+			serde_json::from_str::<Matrix>(&format!(
+				r#"{{"rows":{rows},"state":{state},"row_sequence":{row_sequence},"col_sequence":{col_sequence},"pivot_strategy":"Natural","tolerance":{tolerance:?},"operations":[]}}"#
+			))
+		};
+		let matrix = |rows: &str, state: &str, row_sequence: &str, col_sequence: &str| {
+			with_tolerance(rows, state, row_sequence, col_sequence, 0.0)
+		};
+		let rejects = |m: Result<Matrix, serde_json::Error>, e: InvalidMatrix| {
+			m.is_err_and(|m| m.to_string().starts_with(&e.to_string()))
+		};
+		let one = r#"[{"left":["1"],"right":["1"]}]"#;
+		let two = r#"[{"left":["1","2"],"right":["1"]},{"left":["1","2"],"right":["1"]}]"#;
+		assert!(matrix(one, r#"{"Null":0}"#, "[0]", "[0]").is_ok());
+		assert!(matrix(two, r#"{"ReInsertRow":1}"#, "[1,0]", "[1,0]").is_ok());
+		assert!(rejects(
+			matrix(one, r#"{"Null":5}"#, "[0]", "[0]"),
+			InvalidMatrix::State(MatrixState::Null(5))
+		));
+		assert!(rejects(
+			matrix(two, r#"{"NormalizeRow":2}"#, "[0,1]", "[0,1]"),
+			InvalidMatrix::State(MatrixState::NormalizeRow(2))
+		));
+		assert!(rejects(
+			matrix(one, r#"{"Null":0}"#, "[7]", "[0]"),
+			InvalidMatrix::RowSequence
+		));
+		assert!(rejects(
+			matrix(two, r#"{"Null":0}"#, "[1,1]", "[0,1]"),
+			InvalidMatrix::RowSequence
+		));
+		assert!(rejects(
+			matrix(one, r#"{"Null":0}"#, "[0]", "[9]"),
+			InvalidMatrix::ColSequence
+		));
+		assert!(rejects(
+			matrix(
+				r#"[{"left":["1"],"right":["1"]},{"left":["1","2"],"right":["1"]}]"#,
+				r#"{"Null":0}"#,
+				"[0,1]",
+				"[0]"
+			),
+			InvalidMatrix::RowWidth(1)
+		));
+		assert!(rejects(
+			matrix(one, r#""Initial""#, "[0]", "[0]"),
+			InvalidMatrix::State(MatrixState::Initial)
+		));
+		// more non-zero rows than cols after nulling
+		let tall = r#"[{"left":["1","0"],"right":["1"]},{"left":["0","1"],"right":["1"]},{"left":["1","1"],"right":["1"]}]"#;
+		assert!(rejects(
+			matrix(tall, r#"{"NormalizeRow":0}"#, "[0,1,2]", "[0,1]"),
+			InvalidMatrix::State(MatrixState::NormalizeRow(0))
+		));
+		let zero_pivot = r#"[{"left":["0","1"],"right":["1"]},{"left":["1","1"],"right":["1"]}]"#;
+		assert!(matrix(zero_pivot, r#"{"Null":0}"#, "[1,0]", "[0,1]").is_ok());
+		assert!(rejects(
+			matrix(zero_pivot, r#"{"Null":0}"#, "[0,1]", "[0,1]"),
+			InvalidMatrix::State(MatrixState::Null(0))
+		));
+		assert!(rejects(
+			matrix(zero_pivot, r#"{"NormalizeRow":1}"#, "[1,0]", "[1,0]"),
+			InvalidMatrix::State(MatrixState::NormalizeRow(1))
+		));
+		assert!(with_tolerance(one, r#"{"Null":0}"#, "[0]", "[0]", 0.5).is_ok());
+		assert!(rejects(
+			with_tolerance(one, r#"{"Null":0}"#, "[0]", "[0]", -1.0),
+			InvalidMatrix::Tolerance
+		));
+		// JSON has no NaN
+		let nan = RawMatrix {
+			rows: vec![Row::new(vec![Fraction::from(1)], vec![Fraction::from(1)])],
+			state: MatrixState::Null(0),
+			row_sequence: vec![0],
+			col_sequence: vec![0],
+			pivot_strategy: PivotStrategy::Natural,
+			tolerance: f64::NAN,
+			operations: Vec::new(),
+		};
+		assert_eq!(Matrix::try_from(nan).err(), Some(InvalidMatrix::Tolerance));
+	}
 }
@@ -0,0 +1,379 @@
+use std::{
+	error::Error,
+	fmt::{Debug, Display},
+	ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use crate::{big_fraction::BigFraction, big_uint::BigUint, fraction::Fraction, scalar::Scalar};
+
+/// An element of the finite field GF(`P`).
+///
+/// `P` has to be a prime, so every value except zero has an inverse and a zero pivot
+/// is the only way a system can't be divided through. Other moduli don't compile:
+/// ```compile_fail
+/// # use matrix_solver::mod_p::ModP;
+/// let _ = ModP::<6>::new(2);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModP<const P: u64> {
+	value: u64,
+}
+impl<const P: u64> ModP<P> {
+	const PRIME: () = assert!(is_prime(P), "The modulus of `ModP` has to be a prime.");
+
+	pub const ZERO: Self = Self::new(0);
+	pub const ONE: Self = Self::new(1);
+
+	pub const fn new(value: u64) -> Self {
+		let () = Self::PRIME;
+		Self { value: value % P }
+	}
+
+	/// The residue in `0..P`.
+	pub const fn value(&self) -> u64 {
+		self.value
+	}
+
+	/// The multiplicative inverse using the extended euclidean algorithm,
+	/// `None` for zero which is the only value without one.
+	pub fn inverse(&self) -> Option<Self> {
+		let (mut r0, mut r1) = (P as i128, self.value as i128);
+		let (mut t0, mut t1) = (0i128, 1i128);
+		while r1 != 0 {
+			let q = r0 / r1;
+			(r0, r1) = (r1, r0 - q * r1);
+			(t0, t1) = (t1, t0 - q * t1);
+		}
+		if r0 == 1 {
+			Some(Self::new(t0.rem_euclid(P as i128) as u64))
+		} else {
+			None
+		}
+	}
+}
+impl<const P: u64> Scalar for ModP<P> {
+	fn zero() -> Self {
+		Self::ZERO
+	}
+
+	fn one() -> Self {
+		Self::ONE
+	}
+
+	/// Returns `None` when dividing by zero.
+	fn checked_div(&self, rhs: &Self) -> Option<Self> {
+		rhs.inverse().map(|i| *self * i)
+	}
+}
+impl<const P: u64> Default for ModP<P> {
+	fn default() -> Self {
+		Self::ZERO
+	}
+}
+/// Whether `n` is a prime, using the Miller-Rabin test with bases which
+/// make it deterministic for every `u64`.
+const fn is_prime(n: u64) -> bool {
+	const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+	if n < 2 {
+		return false;
+	}
+	let mut i = 0;
+	while i < BASES.len() {
+		if n.is_multiple_of(BASES[i]) {
+			return n == BASES[i];
+		}
+		i += 1;
+	}
+	// n - 1 = d * 2^s with an odd d
+	let (mut d, mut s) = (n - 1, 0);
+	while d % 2 == 0 {
+		d /= 2;
+		s += 1;
+	}
+	let mut i = 0;
+	'bases: while i < BASES.len() {
+		let mut x = pow_mod(BASES[i], d, n);
+		i += 1;
+		if x == 1 || x == n - 1 {
+			continue;
+		}
+		let mut r = 1;
+		while r < s {
+			x = mul_mod(x, x, n);
+			if x == n - 1 {
+				continue 'bases;
+			}
+			r += 1;
+		}
+		return false;
+	}
+	true
+}
+const fn mul_mod(a: u64, b: u64, n: u64) -> u64 {
+	((a as u128 * b as u128) % n as u128) as u64
+}
+const fn pow_mod(mut base: u64, mut exp: u64, n: u64) -> u64 {
+	let mut result = 1;
+	base %= n;
+	while exp > 0 {
+		if exp % 2 == 1 {
+			result = mul_mod(result, base, n);
+		}
+		base = mul_mod(base, base, n);
+		exp /= 2;
+	}
+	result
+}
+/// A fraction whose denominator is a multiple of `modulus`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotInvertible {
+	pub fraction: BigFraction,
+	pub modulus: u64,
+}
+impl Display for NotInvertible {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(
+			f,
+			"`{}` has no value modulo {} since the denominator isn't invertible.",
+			self.fraction, self.modulus
+		)
+	}
+}
+impl Error for NotInvertible {}
+impl<const P: u64> TryFrom<Fraction> for ModP<P> {
+	type Error = NotInvertible;
+
+	fn try_from(f: Fraction) -> Result<Self, Self::Error> {
+		Self::try_from(BigFraction::from(f))
+	}
+}
+impl<const P: u64> TryFrom<BigFraction> for ModP<P> {
+	type Error = NotInvertible;
+
+	fn try_from(f: BigFraction) -> Result<Self, Self::Error> {
+		let reduce = |n: &BigUint| {
+			Self::new(
+				n.div_rem(&BigUint::from(P))
+					.1
+					.to_u64()
+					.expect("the remainder is less than the modulus"),
+			)
+		};
+		let numerator = reduce(f.numerator());
+		reduce(f.denominator())
+			.inverse()
+			.map(|d| if f.is_negative() { -numerator } else { numerator } * d)
+			.ok_or(NotInvertible {
+				fraction: f,
+				modulus: P,
+			})
+	}
+}
+impl<N: Into<ModP<P>>, D: Into<ModP<P>>, const P: u64> From<(N, D)> for ModP<P> {
+	fn from(f: (N, D)) -> Self {
+		f.0.into() / f.1.into()
+	}
+}
+impl<const P: u64> From<i64> for ModP<P> {
+	fn from(n: i64) -> Self {
+		Self::new((n as i128).rem_euclid(P as i128) as u64)
+	}
+}
+impl<const P: u64> From<isize> for ModP<P> {
+	fn from(n: isize) -> Self {
+		Self::from(n as i64)
+	}
+}
+impl<const P: u64> From<i32> for ModP<P> {
+	fn from(n: i32) -> Self {
+		Self::from(n as i64)
+	}
+}
+impl<const P: u64> From<i16> for ModP<P> {
+	fn from(n: i16) -> Self {
+		Self::from(n as i64)
+	}
+}
+impl<const P: u64> From<i8> for ModP<P> {
+	fn from(n: i8) -> Self {
+		Self::from(n as i64)
+	}
+}
+impl<const P: u64> From<u64> for ModP<P> {
+	fn from(n: u64) -> Self {
+		Self::new(n)
+	}
+}
+impl<const P: u64> From<usize> for ModP<P> {
+	fn from(n: usize) -> Self {
+		Self::from(n as u64)
+	}
+}
+impl<const P: u64> From<u32> for ModP<P> {
+	fn from(n: u32) -> Self {
+		Self::from(n as u64)
+	}
+}
+impl<const P: u64> From<u16> for ModP<P> {
+	fn from(n: u16) -> Self {
+		Self::from(n as u64)
+	}
+}
+impl<const P: u64> From<u8> for ModP<P> {
+	fn from(n: u8) -> Self {
+		Self::from(n as u64)
+	}
+}
+impl<const P: u64> Add<ModP<P>> for ModP<P> {
+	type Output = ModP<P>;
+
+	fn add(self, rhs: ModP<P>) -> Self::Output {
+		Self::new(((self.value as u128 + rhs.value as u128) % P as u128) as u64)
+	}
+}
+impl<const P: u64> AddAssign<ModP<P>> for ModP<P> {
+	fn add_assign(&mut self, rhs: ModP<P>) {
+		*self = *self + rhs;
+	}
+}
+impl<const P: u64> Sub<ModP<P>> for ModP<P> {
+	type Output = ModP<P>;
+
+	fn sub(self, rhs: ModP<P>) -> Self::Output {
+		self + -rhs
+	}
+}
+impl<const P: u64> SubAssign<ModP<P>> for ModP<P> {
+	fn sub_assign(&mut self, rhs: ModP<P>) {
+		*self += -rhs;
+	}
+}
+impl<const P: u64> Mul<ModP<P>> for ModP<P> {
+	type Output = ModP<P>;
+
+	fn mul(self, rhs: ModP<P>) -> Self::Output {
+		Self::new(((self.value as u128 * rhs.value as u128) % P as u128) as u64)
+	}
+}
+impl<const P: u64> MulAssign<ModP<P>> for ModP<P> {
+	fn mul_assign(&mut self, rhs: ModP<P>) {
+		*self = *self * rhs;
+	}
+}
+impl<const P: u64> Div<ModP<P>> for ModP<P> {
+	type Output = ModP<P>;
+
+	#[allow(clippy::suspicious_arithmetic_impl)]
+	fn div(self, rhs: ModP<P>) -> Self::Output {
+		self * rhs.inverse().expect("attempt to divide by zero")
+	}
+}
+impl<const P: u64> DivAssign<ModP<P>> for ModP<P> {
+	fn div_assign(&mut self, rhs: ModP<P>) {
+		*self = *self / rhs;
+	}
+}
+impl<const P: u64> Neg for ModP<P> {
+	type Output = ModP<P>;
+
+	fn neg(self) -> Self::Output {
+		Self::new(P - self.value)
+	}
+}
+impl<const P: u64> Display for ModP<P> {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		Display::fmt(&self.value, f)
+	}
+}
+impl<const P: u64> Debug for ModP<P> {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}", self)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		big_fraction::BigFraction,
+		fraction::Fraction,
+		lu::LuError,
+		matrix,
+		matrix::{InverseError, MatrixState},
+		mod_p::{is_prime, ModP},
+		parser, solve,
+	};
+
+	type M7 = ModP<7>;
+
+	#[test]
+	fn conversions() {
+		assert_eq!(M7::from(-1), M7::new(6));
+		assert_eq!(M7::from(15u8), M7::new(1));
+		assert_eq!(M7::from((1, 3)), M7::new(5));
+		assert_eq!(M7::try_from(Fraction::negative(1, 2)), Ok(M7::new(3)));
+		assert!(M7::try_from(Fraction::positive(1, 14)).is_err());
+		let huge = "100000000000000000000000000000/3"
+			.parse::<BigFraction>()
+			.unwrap();
+		// 10^29 ≡ 3^29 ≡ 5 (mod 7) and 1/3 ≡ 5
+		assert_eq!(M7::try_from(huge), Ok(M7::new(4)));
+	}
+
+	#[test]
+	fn arithmetic() {
+		assert_eq!(M7::new(5) + M7::new(4), M7::new(2));
+		assert_eq!(M7::new(2) - M7::new(4), M7::new(5));
+		assert_eq!(M7::new(3) * M7::new(5), M7::new(1));
+		assert_eq!(M7::new(1) / M7::new(3), M7::new(5));
+		assert_eq!(-M7::ZERO, M7::ZERO);
+		assert_eq!(M7::ZERO.inverse(), None);
+		assert_eq!(
+			ModP::<{ u64::MAX - 58 }>::new(u64::MAX - 59) * ModP::new(u64::MAX - 59),
+			ModP::ONE
+		);
+	}
+
+	#[test]
+	fn primes() {
+		let primes = (0..100).filter(|n| is_prime(*n)).collect::<Vec<u64>>();
+		assert_eq!(
+			primes,
+			vec![
+				2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79,
+				83, 89, 97
+			]
+		);
+		assert!(is_prime(u64::MAX - 58));
+		// strong pseudoprimes to several of the bases
+		assert!(!is_prime(3_215_031_751));
+		assert!(!is_prime(3_825_123_056_546_413_051));
+		assert!(!is_prime(u64::MAX));
+	}
+
+	#[test]
+	fn solve_mod_p() {
+		assert_eq!(
+			solve(matrix![M7; [1; 2] | [3], [3; 1] | [4]]),
+			matrix![M7; [1; 0] | [1], [0; 1] | [1]]
+		);
+		assert_eq!(
+			solve(parser::parse_as::<M7>("(1;2|3)\n(3;1|4)").unwrap()),
+			matrix![M7; [1; 0] | [1], [0; 1] | [1]]
+		);
+	}
+
+	#[test]
+	fn singular() {
+		// 1 * 1 - 2 * 3 = -5, which is zero modulo 5
+		let cut = matrix![ModP<5>; [1; 2] | [1], [3; 1] | [1]];
+		let solved = solve(cut.clone());
+		assert_eq!(solved.state(), &MatrixState::Done);
+		assert_eq!(solved.general_solution(), None);
+		assert_eq!(cut.inverse(), Err(InverseError::Singular));
+		assert_eq!(cut.lu(), Err(LuError::Singular));
+		assert_eq!(cut.try_determinant(), Ok(ModP::ZERO));
+		assert!(solve(matrix![[1; 2] | [1], [3; 1] | [1]])
+			.general_solution()
+			.is_some());
+	}
+}
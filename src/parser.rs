@@ -1,70 +1,425 @@
-use crate::{fraction::Fraction, matrix::Matrix, row::Row};
+use std::{error::Error, fmt::Display};
+
+use crate::{
+	big_fraction::BigFraction,
+	fraction::ParseFractionError,
+	matrix::{Matrix, PivotStrategy},
+	row::Row,
+	scalar::Scalar,
+};
+
+/// Where a [`ParseError`] occurred, `line` and `column` start at 1 and `column` counts bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+	pub line: usize,
+	pub column: usize,
+	pub token: String,
+}
+impl Display for Span {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}:{}", self.line, self.column)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+	/// The row doesn't start with a `(`, the token is the whole row.
+	MissingOpenParen(Span),
+	/// The row doesn't end with a `)`, the token is the whole row.
+	MissingCloseParen(Span),
+	/// The row doesn't have a `|`, the token is the whole row.
+	MissingPipe(Span),
+	/// The cell or coefficient is neither a number nor a fraction.
+	BadFraction(Span),
+	/// The cell is a fraction with a zero denominator.
+	ZeroDenominator(Span),
+	/// The side of a row doesn't have as many cells as the first row,
+	/// the token is the whole side.
+	WrongColumnCount {
+		span: Span,
+		expected: usize,
+		found: usize,
+	},
+	/// The cell can't be converted into the [`Scalar`] of the matrix,
+	/// e.g. because it doesn't fit into a [`Fraction`](crate::fraction::Fraction).
+	NotRepresentable { span: Span, reason: String },
+	/// The equation doesn't have a `=`, the token is the whole equation.
+	MissingEquals(Span),
+	/// A side of an equation is empty or a sign isn't followed by a term,
+	/// the token is the sign or empty.
+	MissingTerm(Span),
+	/// A character which can't start a term or follow the term before.
+	UnexpectedToken(Span),
+}
+impl ParseError {
+	pub fn span(&self) -> &Span {
+		match self {
+			ParseError::MissingOpenParen(span)
+			| ParseError::MissingCloseParen(span)
+			| ParseError::MissingPipe(span)
+			| ParseError::BadFraction(span)
+			| ParseError::ZeroDenominator(span)
+			| ParseError::WrongColumnCount { span, .. }
+			| ParseError::NotRepresentable { span, .. }
+			| ParseError::MissingEquals(span)
+			| ParseError::MissingTerm(span)
+			| ParseError::UnexpectedToken(span) => span,
+		}
+	}
+}
+impl Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		let span = self.span();
+		write!(f, "{}: `{}` ", span, span.token)?;
+		match self {
+			ParseError::MissingOpenParen(_) => write!(f, "doesn't start with a `(`."),
+			ParseError::MissingCloseParen(_) => write!(f, "doesn't end with a `)`."),
+			ParseError::MissingPipe(_) => write!(f, "doesn't have a `|`."),
+			ParseError::BadFraction(_) => write!(f, "can't be parsed as a fraction."),
+			ParseError::ZeroDenominator(_) => write!(f, "has a zero denominator."),
+			ParseError::WrongColumnCount {
+				expected, found, ..
+			} => write!(f, "has {found} fractions instead of {expected}."),
+			ParseError::NotRepresentable { reason, .. } => write!(f, "can't be used: {reason}"),
+			ParseError::MissingEquals(_) => write!(f, "doesn't have a `=`."),
+			ParseError::MissingTerm(_) => write!(f, "isn't followed by a term."),
+			ParseError::UnexpectedToken(_) => write!(f, "is unexpected."),
+		}
+	}
+}
+impl Error for ParseError {}
 
 /// format:
 /// (a;b;c|e;f)
 /// (g;h;i|j;k)
 /// (l;m;n|o;p)
-/// where each char can be a number or number/number (fraction)
-pub fn parse(s: &str) -> Result<Matrix, String> {
-	let mut rows = Vec::with_capacity(s.lines().count());
-	for line in s.lines() {
+/// where each char can be a number or number/number (fraction),
+/// see [`Fraction::from_str`](std::str::FromStr::from_str) for the accepted numbers
+pub fn parse(s: &str) -> Result<Matrix, ParseError> {
+	parse_as(s)
+}
+
+/// Like [`parse_as`], but orders the pivots only with `strategy`,
+/// see [`Matrix::create_with_pivot_strategy`].
+pub fn parse_with_pivot_strategy<T: Scalar + TryFrom<BigFraction>>(
+	s: &str,
+	strategy: PivotStrategy,
+) -> Result<Matrix<T>, ParseError>
+where
+	T::Error: Display,
+{
+	parse_rows(s).map(|rows| Matrix::create_with_pivot_strategy(rows, strategy))
+}
+
+/// Like [`parse`], but converts every cell into another [`Scalar`]. The cells are read
+/// as [`BigFraction`]s first, so e.g. a [`BigFraction`] matrix can have cells which
+/// don't fit into a [`Fraction`](crate::fraction::Fraction).
+pub fn parse_as<T: Scalar + TryFrom<BigFraction>>(s: &str) -> Result<Matrix<T>, ParseError>
+where
+	T::Error: Display,
+{
+	parse_rows(s).map(Matrix::create_with_rows)
+}
+
+fn parse_rows<T: Scalar + TryFrom<BigFraction>>(s: &str) -> Result<Vec<Row<T>>, ParseError>
+where
+	T::Error: Display,
+{
+	let mut rows: Vec<Row<T>> = Vec::with_capacity(s.lines().count());
+	for (n, line) in s.lines().enumerate() {
+		let span = |column: usize, token: &str| Span {
+			line: n + 1,
+			column: column + 1,
+			token: token.to_string(),
+		};
 		if !line.starts_with('(') {
-			return Err(format!(
-				"`{line}` doesn't start with a `(`."
-			));
+			return Err(ParseError::MissingOpenParen(span(0, line)));
 		}
-		if !line.ends_with(')') {
-			return Err(format!(
-				"`{line}` doesn't end with a `)`."
-			));
+		if line.len() < 2 || !line.ends_with(')') {
+			return Err(ParseError::MissingCloseParen(span(0, line)));
 		}
-		if let Some((left, right)) = line[1..line.len() - 1].split_once('|') {
-			rows.push(Row::new(
-				parse_side(left).and_then(|l| {
-					if l.len() == rows.capacity() {
-						Ok(l)
-					} else {
-						Err(format!(
-							"`{line}` has not {} fractions.",
-							rows.capacity()
-						))
-					}
-				})?,
-				parse_side(right)?,
-			));
-		} else {
-			return Err(format!(
-				"`{line}` doesn't have a `|`."
-			));
+		let Some((left, right)) = line[1..line.len() - 1].split_once('|') else {
+			return Err(ParseError::MissingPipe(span(0, line)));
+		};
+		let left_cells = parse_side(left, n + 1, 1)?;
+		let right_cells = parse_side(right, n + 1, left.len() + 2)?;
+		if let Some(first) = rows.first() {
+			for (cells, side, column, expected) in [
+				(&left_cells, left, 1, first.left().len()),
+				(&right_cells, right, left.len() + 2, first.right().len()),
+			] {
+				if cells.len() != expected {
+					return Err(ParseError::WrongColumnCount {
+						span: span(column, side),
+						expected,
+						found: cells.len(),
+					});
+				}
+			}
 		}
+		rows.push(Row::new(left_cells, right_cells));
 	}
-	Ok(Matrix::create_with_rows(rows))
+	Ok(rows)
 }
 
-fn parse_side(s: &str) -> Result<Vec<Fraction>, String> {
-	let mut left_nums = Vec::with_capacity(s.split(';').count());
+/// Parses the cells of one side starting at the 0-indexed byte `column` of `line`.
+fn parse_side<T: TryFrom<BigFraction>>(
+	s: &str,
+	line: usize,
+	mut column: usize,
+) -> Result<Vec<T>, ParseError>
+where
+	T::Error: Display,
+{
+	let mut nums = Vec::with_capacity(s.split(';').count());
 	for f in s.split(';') {
-		if let Some(fraction) = f.parse::<isize>().ok().map(Fraction::from).or_else(|| {
-			f.split_once('/').and_then(|(l, r)| {
-				l.parse::<isize>()
-					.ok()
-					.and_then(|l| r.parse::<isize>().ok().map(|r| (l, r)))
-					.map(Fraction::from)
-			})
+		let span = Span {
+			line,
+			column: column + 1,
+			token: f.to_string(),
+		};
+		column += f.len() + 1;
+		let fraction = match f.parse::<BigFraction>() {
+			Ok(fraction) => fraction,
+			Err(ParseFractionError::Invalid) => return Err(ParseError::BadFraction(span)),
+			Err(ParseFractionError::ZeroDenominator) => {
+				return Err(ParseError::ZeroDenominator(span))
+			}
+			Err(e @ ParseFractionError::Overflow) => {
+				return Err(ParseError::NotRepresentable {
+					span,
+					reason: e.to_string(),
+				})
+			}
+		};
+		nums.push(
+			T::try_from(fraction).map_err(|e| ParseError::NotRepresentable {
+				span,
+				reason: e.to_string(),
+			})?,
+		);
+	}
+	Ok(nums)
+}
+
+/// Parses one linear equation per line like `2x + 3y - z = 5`, returning the matrix
+/// and the names of the variables in the order of their columns.
+///
+/// Terms can be on both sides and a coefficient can be omitted or followed by a `*`,
+/// see [`Fraction::from_str`](std::str::FromStr::from_str) for the accepted numbers.
+/// Variables missing in an equation have the coefficient zero, blank lines are skipped.
+pub fn parse_equations(s: &str) -> Result<(Matrix, Vec<String>), ParseError> {
+	parse_equations_as(s)
+}
+
+/// Like [`parse_equations_as`], but orders the pivots only with `strategy`,
+/// see [`Matrix::create_with_pivot_strategy`].
+pub fn parse_equations_with_pivot_strategy<T: Scalar + TryFrom<BigFraction>>(
+	s: &str,
+	strategy: PivotStrategy,
+) -> Result<(Matrix<T>, Vec<String>), ParseError>
+where
+	T::Error: Display,
+{
+	parse_equation_rows(s)
+		.map(|(rows, names)| (Matrix::create_with_pivot_strategy(rows, strategy), names))
+}
+
+/// Like [`parse_equations`], but converts every coefficient into another [`Scalar`],
+/// see [`parse_as`].
+pub fn parse_equations_as<T: Scalar + TryFrom<BigFraction>>(
+	s: &str,
+) -> Result<(Matrix<T>, Vec<String>), ParseError>
+where
+	T::Error: Display,
+{
+	parse_equation_rows(s).map(|(rows, names)| (Matrix::create_with_rows(rows), names))
+}
+
+/// The rows of the equations and the names of the variables.
+fn parse_equation_rows<T: Scalar + TryFrom<BigFraction>>(
+	s: &str,
+) -> Result<(Vec<Row<T>>, Vec<String>), ParseError>
+where
+	T::Error: Display,
+{
+	let mut names: Vec<String> = Vec::new();
+	let mut equations: Vec<(Vec<T>, T)> = Vec::new();
+	for (n, line) in s.lines().enumerate() {
+		if line.trim().is_empty() {
+			continue;
+		}
+		let Some((left, right)) = line.split_once('=') else {
+			return Err(ParseError::MissingEquals(Span {
+				line: n + 1,
+				column: 1,
+				token: line.to_string(),
+			}));
+		};
+		let mut coefficients = Vec::new();
+		let mut constant = T::zero();
+		for (side, column, negate) in [(left, 0, false), (right, left.len() + 1, true)] {
+			for (coefficient, variable, span) in parse_terms(side, n + 1, column)? {
+				let not_representable = |reason: String| ParseError::NotRepresentable {
+					span: span.clone(),
+					reason,
+				};
+				// variables are moved to the left and constants to the right side
+				let value = T::try_from(if negate ^ variable.is_none() {
+					-coefficient
+				} else {
+					coefficient
+				})
+				.map_err(|e| not_representable(e.to_string()))?;
+				let sum = match variable {
+					Some(name) => {
+						let i = names.iter().position(|n| n == name).unwrap_or_else(|| {
+							names.push(name.to_string());
+							names.len() - 1
+						});
+						if coefficients.len() <= i {
+							coefficients.resize(i + 1, T::zero());
+						}
+						&mut coefficients[i]
+					}
+					None => &mut constant,
+				};
+				*sum = sum
+					.checked_add(&value)
+					.ok_or_else(|| not_representable(String::from("overflow")))?;
+			}
+		}
+		equations.push((coefficients, constant));
+	}
+	let rows = equations
+		.into_iter()
+		.map(|(mut coefficients, constant)| {
+			coefficients.resize(names.len(), T::zero());
+			Row::new(coefficients, vec![constant])
+		})
+		.collect();
+	Ok((rows, names))
+}
+
+/// A coefficient, its variable (`None` for constants) and where it is.
+type Term<'a> = (BigFraction, Option<&'a str>, Span);
+
+/// Splits one side of an equation starting at the 0-indexed byte `column` of `line`
+/// into signed terms like `-2/3x`, `+ y` or `5`.
+fn parse_terms(s: &str, line: usize, column: usize) -> Result<Vec<Term<'_>>, ParseError> {
+	let span = |start: usize, end: usize| Span {
+		line,
+		column: column + start + 1,
+		token: s[start..end].to_string(),
+	};
+	let bytes = s.as_bytes();
+	let skip_whitespace = |mut i: usize| {
+		while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+			i += 1;
+		}
+		i
+	};
+	let unexpected = |i: usize| {
+		let len = s[i..].chars().next().map_or(0, char::len_utf8);
+		ParseError::UnexpectedToken(span(i, i + len))
+	};
+	let mut terms = Vec::new();
+	let mut i = skip_whitespace(0);
+	if i == bytes.len() {
+		return Err(ParseError::MissingTerm(span(i, i)));
+	}
+	while i < bytes.len() {
+		let sign = i;
+		let negative = match bytes[i] {
+			b'-' => true,
+			b'+' => false,
+			_ if terms.is_empty() => false,
+			_ => return Err(unexpected(i)),
+		};
+		if matches!(bytes[i], b'-' | b'+') {
+			i = skip_whitespace(i + 1);
+		}
+		let start = i;
+		i = number_end(bytes, i);
+		let number = &s[start..i];
+		i = skip_whitespace(i);
+		let star = !number.is_empty() && bytes.get(i) == Some(&b'*');
+		if star {
+			i = skip_whitespace(i + 1);
+		}
+		let name_start = i;
+		while bytes.get(i).is_some_and(|b| {
+			b.is_ascii_alphabetic() || *b == b'_' || (i > name_start && b.is_ascii_digit())
 		}) {
-			left_nums.push(fraction);
+			i += 1;
+		}
+		let variable = (i > name_start).then(|| &s[name_start..i]);
+		if variable.is_none() && (number.is_empty() || star) {
+			return match bytes.get(i) {
+				None | Some(b'+' | b'-') => Err(ParseError::MissingTerm(span(sign, sign + 1))),
+				Some(_) => Err(unexpected(i)),
+			};
+		}
+		let end = if variable.is_some() {
+			i
+		} else {
+			start + number.len()
+		};
+		let coefficient = if number.is_empty() {
+			BigFraction::one()
 		} else {
-			return Err(format!(
-				"Fraction `{f}` can't be parsed."
-			));
+			let number_span = span(start, start + number.len());
+			number.parse::<BigFraction>().map_err(|e| match e {
+				ParseFractionError::Invalid => ParseError::BadFraction(number_span),
+				ParseFractionError::ZeroDenominator => ParseError::ZeroDenominator(number_span),
+				ParseFractionError::Overflow => ParseError::NotRepresentable {
+					span: number_span,
+					reason: e.to_string(),
+				},
+			})?
+		};
+		terms.push((
+			if negative { -coefficient } else { coefficient },
+			variable,
+			span(start, end),
+		));
+		i = skip_whitespace(i);
+	}
+	Ok(terms)
+}
+
+/// The end of the number starting at `i`, `i` itself if there is none.
+fn number_end(bytes: &[u8], mut i: usize) -> usize {
+	let digit = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_digit);
+	if !digit(i) && bytes.get(i) != Some(&b'.') {
+		return i;
+	}
+	while i < bytes.len() {
+		match bytes[i] {
+			b'0'..=b'9' | b'.' | b'_' | b'/' => i += 1,
+			b'e' | b'E' if digit(i + 1) => i += 2,
+			b'e' | b'E' if matches!(bytes.get(i + 1), Some(b'+' | b'-')) && digit(i + 2) => i += 3,
+			_ => break,
 		}
 	}
-	Ok(left_nums)
+	i
 }
 
 #[cfg(test)]
 mod test {
-	use crate::{matrix, parser::parse};
+	use crate::{
+		big_fraction::BigFraction,
+		fraction::Fraction,
+		matrix,
+		matrix::PivotStrategy,
+		mod_p::ModP,
+		parser::{
+			parse, parse_as, parse_equations, parse_equations_as,
+			parse_equations_with_pivot_strategy, parse_with_pivot_strategy, ParseError, Span,
+		},
+		solve,
+		solver::MatrixSolver,
+	};
 
 	#[test]
 	fn simple() {
@@ -83,13 +438,145 @@ mod test {
 		);
 	}
 
-    #[test]
-    fn fails() {
-        assert!(parse("1|3").is_err());
-        assert!(parse("(2|3").is_err());
-        assert!(parse("2|3)").is_err());
-        assert!(parse("(2|3)\n(3;4|4)").is_err());
-        assert!(parse("(2;3|").is_err());
-        assert!(parse("(2;3)").is_err());
-    }
+	#[test]
+	fn fails() {
+		assert!(parse("1|3").is_err());
+		assert!(parse("(2|3").is_err());
+		assert!(parse("2|3)").is_err());
+		assert!(parse("(2|3)\n(3;4|4)").is_err());
+		assert!(parse("(2;3|").is_err());
+		assert!(parse("(2;3)").is_err());
+	}
+
+	#[test]
+	fn decimals() {
+		assert_eq!(
+			parse("(0.125;-3.5e-2|1_000)"),
+			Ok(matrix![[(1, 8); (-7, 200)] | [1000]])
+		);
+	}
+
+	#[test]
+	fn spans() {
+		let span = |line, column, token: &str| Span {
+			line,
+			column,
+			token: token.to_string(),
+		};
+		assert_eq!(
+			parse("(1;2|3)\n1;2|3)"),
+			Err(ParseError::MissingOpenParen(span(2, 1, "1;2|3)")))
+		);
+		assert_eq!(
+			parse("(1;2|3)\n(4;x|6)"),
+			Err(ParseError::BadFraction(span(2, 4, "x")))
+		);
+		assert_eq!(
+			parse("(1;2|3;1/0)"),
+			Err(ParseError::ZeroDenominator(span(1, 8, "1/0")))
+		);
+		assert_eq!(
+			parse("(1;2|3)\n(4;5;6|7)"),
+			Err(ParseError::WrongColumnCount {
+				span: span(2, 2, "4;5;6"),
+				expected: 2,
+				found: 3
+			})
+		);
+		assert!(matches!(
+			parse_as::<ModP<7>>("(1;1/7|3)"),
+			Err(ParseError::NotRepresentable { span: s, .. }) if s == span(1, 4, "1/7")
+		));
+		assert!(matches!(
+			parse("(1e40|1)"),
+			Err(ParseError::NotRepresentable { span: s, .. }) if s == span(1, 2, "1e40")
+		));
+		assert_eq!(
+			parse_as::<BigFraction>("(1e40|1)").unwrap().rows()[0][0],
+			"1e40".parse::<BigFraction>().unwrap()
+		);
+		assert!(matches!(
+			parse_equations_as::<BigFraction>("2x = 1e400000"),
+			Err(ParseError::NotRepresentable { span: s, .. }) if s == span(1, 6, "1e400000")
+		));
+		let (m, _) = parse_equations_as::<BigFraction>("1e30 x = 1/1e30").unwrap();
+		assert_eq!(
+			solve(m).rows()[0].right()[0],
+			"1e-60".parse::<BigFraction>().unwrap()
+		);
+		assert_eq!(
+			parse("(1;2|3)\n(4;x|6)").unwrap_err().to_string(),
+			"2:4: `x` can't be parsed as a fraction."
+		);
+	}
+
+	#[test]
+	fn equations() {
+		let (m, names) = parse_equations("2x + 3y - z = 5\nx - y = 0\n\n  z = 1 + x").unwrap();
+		assert_eq!(names, vec!["x", "y", "z"]);
+		assert_eq!(
+			m,
+			matrix![[2; 3; -1] | [5], [1; -1; 0] | [0], [-1; 0; 1] | [1]]
+		);
+
+		let (m, names) = parse_equations("-x + 0.5*y_1 = 2y_1 - 1\n3 = 1/2 x").unwrap();
+		assert_eq!(names, vec!["x", "y_1"]);
+		assert_eq!(m, matrix![[-1; (-3, 2)] | [-1], [(-1, 2); 0] | [-3]]);
+
+		let mut solver = MatrixSolver::new(m).with_variable_names(names);
+		solver.solve();
+		assert!(solver.to_string().ends_with("y_1 = -10/3\nx   =     6\n"));
+	}
+
+	#[test]
+	fn pivot_strategy() {
+		let m = parse_with_pivot_strategy("(0;1|1)\n(1;0|1)", PivotStrategy::Natural).unwrap();
+		assert_eq!(m.pivot_strategy(), PivotStrategy::Natural);
+		assert_eq!(m, matrix![[0; 1] | [1], [1; 0] | [1]]);
+
+		let (m, names) = parse_equations_with_pivot_strategy::<Fraction>(
+			"x + y = 2\nx = 1",
+			PivotStrategy::GreedyMaxZeros,
+		)
+		.unwrap();
+		assert_eq!(m.pivot_strategy(), PivotStrategy::GreedyMaxZeros);
+		assert_eq!(names, vec!["x", "y"]);
+	}
+
+	#[test]
+	fn equation_errors() {
+		let span = |line, column, token: &str| Span {
+			line,
+			column,
+			token: token.to_string(),
+		};
+		assert_eq!(
+			parse_equations("x = 1\n2x + 3y"),
+			Err(ParseError::MissingEquals(span(2, 1, "2x + 3y")))
+		);
+		assert_eq!(
+			parse_equations("2x + = 1"),
+			Err(ParseError::MissingTerm(span(1, 4, "+")))
+		);
+		assert_eq!(
+			parse_equations("2x = "),
+			Err(ParseError::MissingTerm(span(1, 6, "")))
+		);
+		assert_eq!(
+			parse_equations("2x 3y = 1"),
+			Err(ParseError::UnexpectedToken(span(1, 4, "3")))
+		);
+		assert_eq!(
+			parse_equations("x = 1 = 2"),
+			Err(ParseError::UnexpectedToken(span(1, 7, "=")))
+		);
+		assert_eq!(
+			parse_equations("2/0x = 1"),
+			Err(ParseError::ZeroDenominator(span(1, 1, "2/0")))
+		);
+		assert_eq!(
+			parse_equations("2.x.5 = 1"),
+			Err(ParseError::UnexpectedToken(span(1, 4, ".")))
+		);
+	}
 }
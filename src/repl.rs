@@ -0,0 +1,251 @@
+use std::io::{self, BufRead, Write};
+
+use matrix_solver::{
+	fraction::Fraction,
+	matrix::{Cell, Matrix, MatrixState},
+	parser::parse,
+};
+
+const HELP: &str = "\
+Enter a matrix one row per line like `(1;2|3)`, followed by an empty line.
+
+Commands:
+  next               Calculate the next step
+  back               Undo the last step or row operation
+  run                Calculate all remaining steps
+  show               Print the current matrix and what the next step does
+  swap <a> <b>       Swap rows a and b
+  scale <row> <f>    Multiply a row by the non-zero fraction f
+  add <a> <b> <f>    Add f times row b to row a
+  help               Print this help
+  quit               Exit";
+
+/// The history of the matrix entered last and the rows of the one being entered.
+#[derive(Default)]
+pub struct Repl {
+	history: Vec<Matrix>,
+	pending: Vec<String>,
+}
+impl Repl {
+	/// Runs a command or reads a row, returning what to print.
+	/// `None` means the session ended.
+	pub fn execute(&mut self, line: &str) -> Option<String> {
+		let line = line.trim();
+		if line.starts_with('(') {
+			self.pending.push(line.to_string());
+			return Some(String::new());
+		}
+		if line.is_empty() {
+			return Some(self.load());
+		}
+		let words = line.split_whitespace().collect::<Vec<&str>>();
+		Some(match words.as_slice() {
+			["quit" | "exit"] => return None,
+			["help"] => HELP.to_string(),
+			_ if self.history.is_empty() => String::from("Enter a matrix first, see `help`."),
+			["next"] => self.next().unwrap_or_else(|e| e),
+			["back"] => {
+				if self.history.len() > 1 {
+					self.history.pop();
+					self.show()
+				} else {
+					String::from("Already at the initial matrix.")
+				}
+			}
+			["run"] => {
+				let mut steps = Vec::new();
+				while self.current().state() != &MatrixState::Done {
+					match self.next() {
+						Ok(step) => steps.push(step),
+						Err(e) => {
+							steps.push(e);
+							break;
+						}
+					}
+				}
+				if steps.is_empty() {
+					String::from("Already done.")
+				} else {
+					steps.join("\n\n")
+				}
+			}
+			["show"] => self.show(),
+			["swap", a, b] => match (self.row(a), self.row(b)) {
+				(Ok(a), Ok(b)) => {
+					let swapped = self.current().swap_rows(a, b);
+					self.push(Ok(swapped))
+				}
+				(Err(e), _) | (_, Err(e)) => e,
+			},
+			["scale", row, factor] => match (self.row(row), factor.parse::<Fraction>()) {
+				(Ok(_), Ok(f)) if f.sign() == 0 => String::from("Can't scale a row by zero."),
+				(Ok(row), Ok(f)) => {
+					let scaled = self.current().scale_row(row, &f);
+					self.push(scaled)
+				}
+				(Err(e), _) => e,
+				(_, Err(e)) => format!("`{factor}` isn't a fraction: {e}."),
+			},
+			["add", a, b, factor] => match (self.row(a), self.row(b), factor.parse::<Fraction>()) {
+				(Ok(a), Ok(b), Ok(f)) => {
+					let added = self.current().add_row_multiple(a, b, &f);
+					self.push(added)
+				}
+				(Err(e), _, _) | (_, Err(e), _) => e,
+				(_, _, Err(e)) => format!("`{factor}` isn't a fraction: {e}."),
+			},
+			_ => format!("Unknown command `{line}`, see `help`."),
+		})
+	}
+
+	fn current(&self) -> &Matrix {
+		&self.history[self.history.len() - 1]
+	}
+
+	fn show(&self) -> String {
+		format!(
+			"{}{}\n\nNext: {}",
+			self.operations(),
+			self.current(),
+			self.current().state()
+		)
+	}
+
+	/// The row operations of the current step, one per line.
+	fn operations(&self) -> String {
+		self.current()
+			.operations()
+			.iter()
+			.map(|o| format!("{o}\n"))
+			.collect()
+	}
+
+	/// Parses the rows entered since the last matrix and starts a new history.
+	fn load(&mut self) -> String {
+		if self.pending.is_empty() {
+			return String::new();
+		}
+		let rows = self.pending.drain(..).collect::<Vec<String>>().join("\n");
+		match parse(&rows) {
+			Ok(m) => {
+				self.history = vec![m];
+				self.show()
+			}
+			Err(e) => e.to_string(),
+		}
+	}
+
+	/// Calculates the next step and prints the transition.
+	fn next(&mut self) -> Result<String, String> {
+		let before = self.current().state().clone();
+		match self.current().try_calculate_next() {
+			Ok(Some(m)) => {
+				self.history.push(m);
+				Ok(format!(
+					"{} => {}\n{}{}",
+					before,
+					self.current().state(),
+					self.operations(),
+					self.current()
+				))
+			}
+			Ok(None) => Err(String::from("Already done.")),
+			Err(Cell { row, col }) => Err(format!(
+				"Overflow in row {} at index {col} while {before}.",
+				row + 1
+			)),
+		}
+	}
+
+	fn push(&mut self, m: Result<Matrix, Cell>) -> String {
+		match m {
+			Ok(m) => {
+				self.history.push(m);
+				self.show()
+			}
+			Err(Cell { row, col }) => format!("Overflow in row {} at index {col}.", row + 1),
+		}
+	}
+
+	/// Parses a 1-indexed row number.
+	fn row(&self, s: &str) -> Result<usize, String> {
+		match s.parse::<usize>() {
+			Ok(row) if (1..=self.current().rows().len()).contains(&row) => Ok(row - 1),
+			_ => Err(format!(
+				"`{s}` isn't a row between 1 and {}.",
+				self.current().rows().len()
+			)),
+		}
+	}
+}
+
+/// Reads commands from `input` until it ends or `quit` is entered.
+pub fn run(input: impl BufRead, output: &mut impl Write) -> io::Result<()> {
+	let mut repl = Repl::default();
+	writeln!(output, "{HELP}")?;
+	write!(output, "> ")?;
+	output.flush()?;
+	for line in input.lines() {
+		match repl.execute(&line?) {
+			Some(s) if s.is_empty() => {}
+			Some(s) => writeln!(output, "{s}")?,
+			None => return Ok(()),
+		}
+		write!(output, "> ")?;
+		output.flush()?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use crate::repl::Repl;
+
+	fn session(lines: &[&str]) -> Vec<String> {
+		let mut repl = Repl::default();
+		lines
+			.iter()
+			.map_while(|l| repl.execute(l))
+			.filter(|s| !s.is_empty())
+			.collect()
+	}
+
+	#[test]
+	fn steps() {
+		let out = session(&[
+			"next", "(1;1|2)", "(1;-1|0)", "", "next", "back", "run", "next",
+		]);
+		assert_eq!(out[0], "Enter a matrix first, see `help`.");
+		assert!(out[1].ends_with("Next: Nulling first 1 col(s)"));
+		assert!(out[2].starts_with("Nulling first 1 col(s) => Normalizing row 2\n"));
+		assert!(out[3].ends_with("Next: Nulling first 1 col(s)"));
+		assert!(out[4].ends_with("=> Done\nR1 ← 1·R1 − 1·R2\n(1 0 | 1)\n(0 1 | 1)"));
+		assert_eq!(out[5], "Already done.");
+	}
+
+	#[test]
+	fn row_operations() {
+		let out = session(&[
+			"(0;2|4)",
+			"(1;1|3)",
+			"",
+			"swap 1 2",
+			"scale 2 1/2",
+			"add 1 2 -1",
+			"scale 1 0",
+			"swap 1 3",
+			"add 1 2 x",
+		]);
+		assert!(out[1].starts_with("R1 ↔ R2\n(1 1 | 3)\n(0 2 | 4)"));
+		assert!(out[2].starts_with("R2 ← 1/2·R2\n(1 1 | 3)\n(0 1 | 2)"));
+		assert!(out[3].starts_with("R1 ← R1 − 1·R2\n(1 0 | 1)\n(0 1 | 2)\n\nNext: Done"));
+		assert_eq!(out[4], "Can't scale a row by zero.");
+		assert_eq!(out[5], "`3` isn't a row between 1 and 2.");
+		assert_eq!(out[6], "`x` isn't a fraction: invalid number.");
+	}
+
+	#[test]
+	fn quit() {
+		assert_eq!(session(&["help", "quit", "help"]).len(), 1);
+	}
+}
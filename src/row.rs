@@ -1,64 +1,108 @@
-use std::ops::{DivAssign, Index, IndexMut, Mul, MulAssign, Sub};
+use std::ops::{Add, DivAssign, Index, IndexMut, Mul, MulAssign, Sub};
 
-use crate::fraction::Fraction;
+use crate::{fraction::Fraction, scalar::Scalar};
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct Row {
-	left: Vec<Fraction>,
-	right: Vec<Fraction>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Row<T = Fraction> {
+	left: Vec<T>,
+	right: Vec<T>,
 }
-impl Row {
-	pub fn new(left: Vec<Fraction>, right: Vec<Fraction>) -> Self {
+impl<T: Scalar> Row<T> {
+	pub fn new(left: Vec<T>, right: Vec<T>) -> Self {
 		Self { left, right }
 	}
 
-	pub fn left(&self) -> &Vec<Fraction> {
+	pub fn left(&self) -> &Vec<T> {
 		&self.left
 	}
 
-	pub fn right(&self) -> &Vec<Fraction> {
+	pub fn right(&self) -> &Vec<T> {
 		&self.right
 	}
+
+	/// Multiplies every cell with `rhs`.
+	/// On overflow the index of the offending cell is returned (see [`Index`]).
+	pub fn checked_mul(&self, rhs: &T) -> Result<Self, isize> {
+		self.try_map(|_, n| n.checked_mul(rhs))
+	}
+
+	/// Divides every cell by `rhs`.
+	/// On overflow the index of the offending cell is returned (see [`Index`]).
+	pub fn checked_div(&self, rhs: &T) -> Result<Self, isize> {
+		self.try_map(|_, n| n.checked_div(rhs))
+	}
+
+	/// Subtracts `rhs` cell by cell.
+	/// On overflow the index of the offending cell is returned (see [`Index`]).
+	pub fn checked_sub(&self, rhs: &Self) -> Result<Self, isize> {
+		self.try_map(|i, n| n.checked_sub(&rhs[i]))
+	}
+
+	fn try_map(&self, f: impl Fn(isize, &T) -> Option<T>) -> Result<Self, isize> {
+		let left = (0..self.left.len() as isize)
+			.map(|i| f(i, &self[i]).ok_or(i))
+			.collect::<Result<Vec<T>, isize>>()?;
+		let right = (0..self.right.len() as isize)
+			.map(|i| -1 - i)
+			.map(|i| f(i, &self[i]).ok_or(i))
+			.collect::<Result<Vec<T>, isize>>()?;
+		Ok(Row::new(left, right))
+	}
 }
-impl MulAssign<Fraction> for Row {
-	fn mul_assign(&mut self, rhs: Fraction) {
-		self.left.iter_mut().for_each(|n| *n *= rhs);
-		self.right.iter_mut().for_each(|n| *n *= rhs);
+impl<T: Scalar> MulAssign<T> for Row<T> {
+	fn mul_assign(&mut self, rhs: T) {
+		self.left
+			.iter_mut()
+			.chain(self.right.iter_mut())
+			.for_each(|n| *n = n.clone() * rhs.clone());
 	}
 }
-impl DivAssign<Fraction> for Row {
-	#[allow(clippy::suspicious_op_assign_impl)]
-	fn div_assign(&mut self, rhs: Fraction) {
-		*self *= rhs.swapped();
+impl<T: Scalar> DivAssign<T> for Row<T> {
+	fn div_assign(&mut self, rhs: T) {
+		self.left
+			.iter_mut()
+			.chain(self.right.iter_mut())
+			.for_each(|n| *n = n.clone() / rhs.clone());
 	}
 }
-impl Mul<Fraction> for Row {
-	type Output = Row;
+impl<T: Scalar> Mul<T> for Row<T> {
+	type Output = Row<T>;
 
-	fn mul(self, rhs: Fraction) -> Self::Output {
+	fn mul(self, rhs: T) -> Self::Output {
 		let mut new = self;
 		new *= rhs;
 		new
 	}
 }
-impl Sub<Row> for Row {
-	type Output = Row;
+impl<T: Scalar> Add<Row<T>> for Row<T> {
+	type Output = Row<T>;
 
-	fn sub(self, rhs: Row) -> Self::Output {
+	fn add(self, rhs: Row<T>) -> Self::Output {
 		let mut new = self;
 		new.left
 			.iter_mut()
-			.enumerate()
-			.for_each(|(i, n)| *n -= rhs.left[i]);
-		new.right
+			.zip(rhs.left)
+			.chain(new.right.iter_mut().zip(rhs.right))
+			.for_each(|(n, r)| *n = n.clone() + r);
+		new
+	}
+}
+impl<T: Scalar> Sub<Row<T>> for Row<T> {
+	type Output = Row<T>;
+
+	fn sub(self, rhs: Row<T>) -> Self::Output {
+		let mut new = self;
+		new.left
 			.iter_mut()
-			.enumerate()
-			.for_each(|(i, n)| *n -= rhs.right[i]);
+			.zip(rhs.left)
+			.chain(new.right.iter_mut().zip(rhs.right))
+			.for_each(|(n, r)| *n = n.clone() - r);
 		new
 	}
 }
-impl Index<isize> for Row {
-	type Output = Fraction;
+impl<T> Index<isize> for Row<T> {
+	type Output = T;
 
 	fn index(&self, index: isize) -> &Self::Output {
 		if index < 0 {
@@ -68,7 +112,7 @@ impl Index<isize> for Row {
 		}
 	}
 }
-impl IndexMut<isize> for Row {
+impl<T> IndexMut<isize> for Row<T> {
 	fn index_mut(&mut self, index: isize) -> &mut Self::Output {
 		if index < 0 {
 			&mut self.right[(-1 - index) as usize]
@@ -93,10 +137,19 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn add() {
+		assert_eq!(
+			Row::<F>::new(vec![0.into(), 1.into(), 2.into()], vec![3.into(), 4.into()])
+				+ Row::new(vec![0.into(), 1.into(), 0.into()], vec![0.into(), 1.into()]),
+			Row::new(vec![0.into(), 2.into(), 2.into()], vec![3.into(), 5.into()])
+		);
+	}
+
 	#[test]
 	fn sub() {
 		assert_eq!(
-			Row::new(vec![0.into(), 1.into(), 2.into()], vec![3.into(), 4.into()])
+			Row::<F>::new(vec![0.into(), 1.into(), 2.into()], vec![3.into(), 4.into()])
 				- Row::new(vec![0.into(), 1.into(), 0.into()], vec![0.into(), 1.into()]),
 			Row::new(vec![0.into(), 0.into(), 2.into()], vec![3.into(), 3.into()])
 		);
@@ -104,11 +157,28 @@ mod test {
 
 	#[test]
 	fn index() {
-		let cut = Row::new(vec![0.into(), 1.into(), 2.into()], vec![3.into(), 4.into()]);
+		let cut: Row = Row::new(vec![0.into(), 1.into(), 2.into()], vec![3.into(), 4.into()]);
 		assert_eq!(cut[0], 0.into());
 		assert_eq!(cut[1], 1.into());
 		assert_eq!(cut[2], 2.into());
 		assert_eq!(cut[-1], 3.into());
 		assert_eq!(cut[-2], 4.into());
 	}
+
+	#[test]
+	fn checked() {
+		let cut = Row::new(
+			vec![1.into(), F::positive_n(usize::MAX)],
+			vec![F::positive_n(usize::MAX)],
+		);
+		assert_eq!(cut.checked_mul(&2.into()), Err(1));
+		assert_eq!(
+			cut.checked_sub(&Row::new(vec![1.into(), 0.into()], vec![F::M_ONE])),
+			Err(-1)
+		);
+		assert_eq!(
+			cut.checked_sub(&cut),
+			Ok(Row::new(vec![0.into(), 0.into()], vec![0.into()]))
+		);
+	}
 }
@@ -0,0 +1,223 @@
+use std::{
+	fmt::{Debug, Display},
+	ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use crate::{big_fraction::BigFraction, fraction::Fraction};
+
+/// A field whose elements can be used in a [`Matrix`](crate::matrix::Matrix).
+///
+/// The checked operations return `None` if the result can't be represented,
+/// which [`MatrixSolver::try_solve`](crate::solver::MatrixSolver::try_solve) reports as an overflow.
+pub trait Scalar:
+	Clone
+	+ PartialEq
+	+ Debug
+	+ Display
+	+ Add<Output = Self>
+	+ Sub<Output = Self>
+	+ Mul<Output = Self>
+	+ Div<Output = Self>
+	+ Neg<Output = Self>
+{
+	/// Whether the arithmetic is free of rounding errors.
+	const EXACT: bool = true;
+
+	fn zero() -> Self;
+
+	fn one() -> Self;
+
+	fn is_zero(&self) -> bool {
+		*self == Self::zero()
+	}
+
+	fn is_one(&self) -> bool {
+		*self == Self::one()
+	}
+
+	/// Only used for formatting, fields without an order are never negative.
+	fn is_negative(&self) -> bool {
+		false
+	}
+
+	fn abs(&self) -> Self {
+		if self.is_negative() {
+			-self.clone()
+		} else {
+			self.clone()
+		}
+	}
+
+	/// How the value is written in LaTeX math mode.
+	fn to_latex(&self) -> String {
+		self.to_string()
+	}
+
+	/// Used for partial pivoting and tolerance-based zero tests,
+	/// fields without an absolute value only tell zero apart.
+	fn magnitude(&self) -> f64 {
+		if self.is_zero() {
+			0.0
+		} else {
+			1.0
+		}
+	}
+
+	fn checked_add(&self, rhs: &Self) -> Option<Self> {
+		Some(self.clone() + rhs.clone())
+	}
+
+	fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+		Some(self.clone() - rhs.clone())
+	}
+
+	fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+		Some(self.clone() * rhs.clone())
+	}
+
+	/// Returns `None` when dividing by zero.
+	fn checked_div(&self, rhs: &Self) -> Option<Self> {
+		if rhs.is_zero() {
+			None
+		} else {
+			Some(self.clone() / rhs.clone())
+		}
+	}
+}
+fn latex_fraction(
+	negative: bool,
+	numerator: impl Display,
+	integer: bool,
+	denominator: impl Display,
+) -> String {
+	let sign = if negative { "-" } else { "" };
+	if integer {
+		format!("{sign}{numerator}")
+	} else {
+		format!("{sign}\\frac{{{numerator}}}{{{denominator}}}")
+	}
+}
+impl Scalar for Fraction {
+	fn zero() -> Self {
+		Fraction::ZERO
+	}
+
+	fn one() -> Self {
+		Fraction::ONE
+	}
+
+	fn is_zero(&self) -> bool {
+		self.sign() == 0
+	}
+
+	fn is_negative(&self) -> bool {
+		Fraction::is_negative(self)
+	}
+
+	fn abs(&self) -> Self {
+		Fraction::abs(self)
+	}
+
+	fn magnitude(&self) -> f64 {
+		self.as_f64().abs()
+	}
+
+	fn to_latex(&self) -> String {
+		latex_fraction(
+			self.is_negative(),
+			self.numerator(),
+			self.denominator() == 1,
+			self.denominator(),
+		)
+	}
+
+	fn checked_add(&self, rhs: &Self) -> Option<Self> {
+		Fraction::checked_add(*self, *rhs)
+	}
+
+	fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+		Fraction::checked_sub(*self, *rhs)
+	}
+
+	fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+		Fraction::checked_mul(*self, *rhs)
+	}
+
+	fn checked_div(&self, rhs: &Self) -> Option<Self> {
+		Fraction::checked_div(*self, *rhs)
+	}
+}
+impl Scalar for BigFraction {
+	fn zero() -> Self {
+		BigFraction::zero()
+	}
+
+	fn one() -> Self {
+		BigFraction::one()
+	}
+
+	fn is_zero(&self) -> bool {
+		self.sign() == 0
+	}
+
+	fn is_negative(&self) -> bool {
+		BigFraction::is_negative(self)
+	}
+
+	fn abs(&self) -> Self {
+		BigFraction::abs(self)
+	}
+
+	fn magnitude(&self) -> f64 {
+		self.as_f64().abs()
+	}
+
+	fn to_latex(&self) -> String {
+		latex_fraction(
+			self.is_negative(),
+			self.numerator(),
+			self.denominator().is_one(),
+			self.denominator(),
+		)
+	}
+}
+/// Overflowing to an infinite value or `NaN` counts as overflow.
+impl Scalar for f64 {
+	const EXACT: bool = false;
+
+	fn zero() -> Self {
+		0.0
+	}
+
+	fn one() -> Self {
+		1.0
+	}
+
+	fn is_negative(&self) -> bool {
+		*self < 0.0
+	}
+
+	fn abs(&self) -> Self {
+		f64::abs(*self)
+	}
+
+	fn magnitude(&self) -> f64 {
+		f64::abs(*self)
+	}
+
+	fn checked_add(&self, rhs: &Self) -> Option<Self> {
+		Some(self + rhs).filter(|r| r.is_finite())
+	}
+
+	fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+		Some(self - rhs).filter(|r| r.is_finite())
+	}
+
+	fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+		Some(self * rhs).filter(|r| r.is_finite())
+	}
+
+	fn checked_div(&self, rhs: &Self) -> Option<Self> {
+		Some(self / rhs).filter(|r| r.is_finite())
+	}
+}
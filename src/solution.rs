@@ -0,0 +1,311 @@
+use crate::{dense::DenseMatrix, fraction::Fraction, matrix::Matrix, scalar::Scalar};
+
+/// All solutions of a solved system, the variables are indexed like the columns of the left side.
+///
+/// For right side `c` and arbitrary values `t_i` the solutions are
+/// `particular[c] + t_0 * null_space[0] + t_1 * null_space[1] + ...`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeneralSolution<T = Fraction> {
+	/// One solution per right side with all free variables set to zero.
+	pub particular: Vec<Vec<T>>,
+	/// The variables which can be chosen freely, ascending.
+	pub free_variables: Vec<usize>,
+	/// A basis of the null space of the left side, the `i`th vector sets
+	/// `free_variables[i]` to one and the other free variables to zero.
+	pub null_space: Vec<Vec<T>>,
+}
+impl<T> GeneralSolution<T> {
+	/// Whether the system has exactly one solution.
+	pub fn is_unique(&self) -> bool {
+		self.free_variables.is_empty()
+	}
+}
+/// How many solutions a solved system has.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "outcome", rename_all = "lowercase"))]
+pub enum SolveOutcome<T = Fraction> {
+	/// Exactly one solution per right side, indexed by variable.
+	Unique { solution: Vec<Vec<T>> },
+	/// Infinitely many solutions, see [`GeneralSolution`].
+	Infinite {
+		free_vars: Vec<usize>,
+		particular: Vec<Vec<T>>,
+		null_space: Vec<Vec<T>>,
+	},
+	/// Equation `row` (indexed like the rows of the initial matrix)
+	/// reduced to `0 = c` with `c ≠ 0`.
+	Inconsistent { row: usize },
+}
+impl<T> From<GeneralSolution<T>> for SolveOutcome<T> {
+	fn from(s: GeneralSolution<T>) -> Self {
+		if s.is_unique() {
+			SolveOutcome::Unique {
+				solution: s.particular,
+			}
+		} else {
+			SolveOutcome::Infinite {
+				free_vars: s.free_variables,
+				particular: s.particular,
+				null_space: s.null_space,
+			}
+		}
+	}
+}
+impl<T> SolveOutcome<T> {
+	/// The general solution, `None` if the system is inconsistent.
+	pub fn general_solution(self) -> Option<GeneralSolution<T>> {
+		match self {
+			SolveOutcome::Unique { solution } => Some(GeneralSolution {
+				particular: solution,
+				free_variables: Vec::new(),
+				null_space: Vec::new(),
+			}),
+			SolveOutcome::Infinite {
+				free_vars,
+				particular,
+				null_space,
+			} => Some(GeneralSolution {
+				particular,
+				free_variables: free_vars,
+				null_space,
+			}),
+			SolveOutcome::Inconsistent { .. } => None,
+		}
+	}
+}
+/// Something a [`Solution`] can depend on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Parameter {
+	/// The parameter of the `i`th right side with `i >= 1`, written as `t, u, ...`.
+	RightSide(usize),
+	/// A variable which can be chosen freely.
+	FreeVariable(usize),
+}
+/// `constant + coefficients[0]·p_0 + coefficients[1]·p_1 + ...`
+/// for the parameters `p_i` of a [`Solution`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AffineExpr<T = Fraction> {
+	pub constant: T,
+	pub coefficients: Vec<T>,
+}
+impl<T: Scalar> AffineExpr<T> {
+	/// Whether it doesn't depend on any parameter.
+	pub fn is_constant(&self) -> bool {
+		self.coefficients.iter().all(Scalar::is_zero)
+	}
+
+	/// The value for the given values of the parameters.
+	pub fn evaluate(&self, parameters: &[T]) -> T {
+		assert_eq!(
+			parameters.len(),
+			self.coefficients.len(),
+			"Every parameter needs a value."
+		);
+		self.coefficients
+			.iter()
+			.zip(parameters)
+			.fold(self.constant.clone(), |sum, (c, p)| {
+				sum + c.clone() * p.clone()
+			})
+	}
+}
+/// All solutions of a system with one expression per variable, indexed like the columns
+/// of the left side. The first right side is the constant, the other right sides and the
+/// free variables are the parameters.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Solution<T = Fraction> {
+	pub parameters: Vec<Parameter>,
+	pub variables: Vec<AffineExpr<T>>,
+}
+impl<T: Scalar> From<GeneralSolution<T>> for Solution<T> {
+	fn from(s: GeneralSolution<T>) -> Self {
+		let parameters = (1..s.particular.len())
+			.map(Parameter::RightSide)
+			.chain(
+				s.free_variables
+					.iter()
+					.copied()
+					.map(Parameter::FreeVariable),
+			)
+			.collect();
+		let width = s
+			.particular
+			.iter()
+			.chain(&s.null_space)
+			.map(Vec::len)
+			.next()
+			.unwrap_or(0);
+		let variables = (0..width)
+			.map(|v| AffineExpr {
+				constant: s.particular.first().map_or(T::zero(), |p| p[v].clone()),
+				coefficients: s.particular[1.min(s.particular.len())..]
+					.iter()
+					.chain(&s.null_space)
+					.map(|p| p[v].clone())
+					.collect(),
+			})
+			.collect();
+		Self {
+			parameters,
+			variables,
+		}
+	}
+}
+/// How far every equation of a system is off after substituting a solution,
+/// see [`MatrixSolver::verify`](crate::solver::MatrixSolver::verify).
+/// The rows are indexed like the rows of the initial matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Residuals<T = Fraction> {
+	/// The right sides which were substituted, ascending.
+	pub right_sides: Vec<usize>,
+	/// `rows[r][i]` is `A_r · particular - b_r` for the right side `right_sides[i]`.
+	pub rows: Vec<Vec<T>>,
+	/// `null_space[r][k]` is `A_r · null_space[k]`, which has to be zero as well.
+	pub null_space: Vec<Vec<T>>,
+}
+impl<T: Scalar> Residuals<T> {
+	/// Like [`Residuals::try_new`], but panics if a cell overflows.
+	pub fn new(
+		initial: &Matrix<T>,
+		right_sides: Vec<usize>,
+		particular: &[Vec<T>],
+		null_space: &[Vec<T>],
+	) -> Self {
+		Self::try_new(initial, right_sides, particular, null_space)
+			.expect("Overflow while substituting the solution.")
+	}
+
+	/// Substitutes the `particular` solutions of `right_sides` and the `null_space`
+	/// into the equations of `initial`, `None` if a cell overflows.
+	pub fn try_new(
+		initial: &Matrix<T>,
+		right_sides: Vec<usize>,
+		particular: &[Vec<T>],
+		null_space: &[Vec<T>],
+	) -> Option<Self> {
+		let left = DenseMatrix::from(initial);
+		let products = particular
+			.iter()
+			.map(|p| left.checked_mul_vector(p))
+			.collect::<Option<Vec<Vec<T>>>>()?;
+		let null_products = null_space
+			.iter()
+			.map(|n| left.checked_mul_vector(n))
+			.collect::<Option<Vec<Vec<T>>>>()?;
+		let rows = initial
+			.rows()
+			.iter()
+			.enumerate()
+			.map(|(r, row)| {
+				right_sides
+					.iter()
+					.zip(&products)
+					.map(|(c, p)| p[r].checked_sub(&row.right()[*c]))
+					.collect()
+			})
+			.collect::<Option<Vec<Vec<T>>>>()?;
+		let null_space = (0..initial.rows().len())
+			.map(|r| null_products.iter().map(|n| n[r].clone()).collect())
+			.collect();
+		Some(Self {
+			right_sides,
+			rows,
+			null_space,
+		})
+	}
+
+	/// Whether every residual is zero.
+	pub fn is_exact(&self) -> bool {
+		self.is_within(0.0)
+	}
+
+	/// Whether the [`Scalar::magnitude`] of every residual is at most `tolerance`.
+	pub fn is_within(&self, tolerance: f64) -> bool {
+		self.wrong_rows(tolerance).is_empty()
+	}
+
+	/// The rows with a residual whose magnitude is larger than `tolerance`, ascending.
+	pub fn wrong_rows(&self, tolerance: f64) -> Vec<usize> {
+		self.rows
+			.iter()
+			.zip(&self.null_space)
+			.enumerate()
+			.filter(|(_, (r, n))| {
+				r.iter()
+					.chain(n.iter())
+					.any(|f| !f.is_zero() && f.magnitude() > tolerance)
+			})
+			.map(|(i, _)| i)
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		fraction::Fraction,
+		matrix,
+		solution::{AffineExpr, Parameter},
+		solver::MatrixSolver,
+	};
+
+	#[test]
+	fn structured_solution() {
+		// x + 2y + z = 1/2 + t, 2x + 4y = 0
+		let mut solver = MatrixSolver::new(matrix![[1; 2; 1] | [(1, 2); 1], [2; 4; 0] | [0; 0]]);
+		assert_eq!(solver.solution(), None);
+		solver.solve();
+		let solution = solver.solution().unwrap();
+		assert_eq!(
+			solution.parameters,
+			vec![Parameter::RightSide(1), Parameter::FreeVariable(1)]
+		);
+		let names = solution
+			.parameters
+			.iter()
+			.map(|p| solver.parameter_name(*p))
+			.collect::<Vec<String>>();
+		assert_eq!(names, vec!["t", "x_2"]);
+		let f = |v: &[i64]| {
+			v.iter()
+				.map(|i| Fraction::from(*i))
+				.collect::<Vec<Fraction>>()
+		};
+		assert_eq!(
+			solution.variables,
+			vec![
+				AffineExpr {
+					constant: Fraction::ZERO,
+					coefficients: f(&[0, -2])
+				},
+				AffineExpr {
+					constant: Fraction::ZERO,
+					coefficients: f(&[0, 1])
+				},
+				AffineExpr {
+					constant: Fraction::positive(1, 2),
+					coefficients: f(&[1, 0])
+				},
+			]
+		);
+		assert!(!solution.variables[2].is_constant());
+		assert_eq!(
+			solution.variables[2].evaluate(&f(&[2, 5])),
+			Fraction::positive(5, 2)
+		);
+
+		let mut solver = MatrixSolver::new(matrix![[1; 1] | [2], [1; -1] | [0]]);
+		solver.solve();
+		let solution = solver.solution().unwrap();
+		assert!(solution.parameters.is_empty());
+		assert!(solution
+			.variables
+			.iter()
+			.all(|v| v.is_constant() && v.constant == Fraction::ONE));
+	}
+}
@@ -1,112 +1,868 @@
-use std::{fmt::Display, ops::Index};
+use std::{error::Error, fmt::Display, ops::Index};
 
-use crate::matrix::{Matrix, MatrixState};
+use crate::{
+	fraction::Fraction,
+	matrix::{Cell, Matrix, MatrixState, PivotStrategy},
+	scalar::Scalar,
+	solution::{GeneralSolution, Parameter, Residuals, Solution, SolveOutcome},
+};
 
-pub struct MatrixSolver {
-	matrices: Vec<Matrix>,
+/// A cell overflowed while calculating step `step` of the history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArithmeticOverflow {
+	pub step: usize,
+	pub state: MatrixState,
+	pub cell: Cell,
 }
-impl MatrixSolver {
-	pub fn new(initial: Matrix) -> Self {
+impl Display for ArithmeticOverflow {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(
+			f,
+			"Arithmetic overflow in step {} ({}) in row {} at index {}.",
+			self.step,
+			self.state,
+			self.cell.row + 1,
+			self.cell.col
+		)
+	}
+}
+impl Error for ArithmeticOverflow {}
+
+/// Why a deserialized [`MatrixSolver`] was rejected.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidHistory {
+	/// There is no initial matrix.
+	Empty,
+	/// The step has another amount of rows, cols or right sides than the initial matrix.
+	Shape(usize),
+}
+#[cfg(feature = "serde")]
+impl Display for InvalidHistory {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			InvalidHistory::Empty => write!(f, "The history needs at least the initial matrix."),
+			InvalidHistory::Shape(step) => write!(
+				f,
+				"Step {step} needs as many rows, cols and right sides as the initial matrix."
+			),
+		}
+	}
+}
+#[cfg(feature = "serde")]
+impl Error for InvalidHistory {}
+
+/// The fields of a [`MatrixSolver`] before they are checked, see [`InvalidHistory`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(bound(deserialize = "T: Scalar + serde::Deserialize<'de>"))]
+struct RawMatrixSolver<T> {
+	matrices: Vec<Matrix<T>>,
+	variable_names: Vec<String>,
+	right_sides: RightSides,
+}
+#[cfg(feature = "serde")]
+impl<T: Scalar> TryFrom<RawMatrixSolver<T>> for MatrixSolver<T> {
+	type Error = InvalidHistory;
+
+	fn try_from(raw: RawMatrixSolver<T>) -> Result<Self, Self::Error> {
+		let shape = |m: &Matrix<T>| {
+			let first = m.rows().first();
+			(
+				m.rows().len(),
+				first.map_or(0, |r| r.left().len()),
+				first.map_or(0, |r| r.right().len()),
+			)
+		};
+		let initial = raw.matrices.first().ok_or(InvalidHistory::Empty)?;
+		if let Some(step) = raw.matrices.iter().position(|m| shape(m) != shape(initial)) {
+			return Err(InvalidHistory::Shape(step));
+		}
+		Ok(Self {
+			matrices: raw.matrices,
+			variable_names: raw.variable_names,
+			right_sides: raw.right_sides,
+		})
+	}
+}
+
+/// How [`MatrixSolver`] reports a matrix with several right sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RightSides {
+	/// The right sides are one system whose constants depend on the parameters
+	/// `t, u, ...`, e.g. `x = 1 + 2t` for the right sides `1` and `2`.
+	#[default]
+	Parametric,
+	/// Every right side is its own system `A·x = b` with its own solution,
+	/// like the columns of `X` in `A·X = B`.
+	Independent,
+}
+
+/// The history of solving a [`Matrix`]: `solver[0]` is the initial matrix and every
+/// following `solver[i]` is one step, carrying the [`Matrix::operations`] which lead to it.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+	feature = "serde",
+	serde(
+		try_from = "RawMatrixSolver<T>",
+		bound(deserialize = "T: Scalar + serde::Deserialize<'de>")
+	)
+)]
+pub struct MatrixSolver<T = Fraction> {
+	matrices: Vec<Matrix<T>>,
+	variable_names: Vec<String>,
+	right_sides: RightSides,
+}
+impl<T: Scalar> MatrixSolver<T> {
+	pub fn new(initial: Matrix<T>) -> Self {
 		Self {
 			matrices: vec![initial],
+			variable_names: Vec::new(),
+			right_sides: RightSides::default(),
+		}
+	}
+
+	/// Uses `strategy` for all remaining steps, see [`Matrix::with_pivot_strategy`].
+	pub fn with_pivot_strategy(mut self, strategy: PivotStrategy) -> Self {
+		if let Some(last) = self.matrices.pop() {
+			self.matrices.push(last.with_pivot_strategy(strategy));
 		}
+		self
+	}
+
+	/// Substitutes the solution into every equation of the initial matrix,
+	/// `None` if there is no solution. With [`RightSides::Independent`]
+	/// the right sides without a solution are skipped.
+	pub fn verify(&self) -> Option<Residuals<T>> {
+		let last = &self[-1];
+		let (right_sides, solution) = match self.right_sides {
+			RightSides::Parametric => {
+				let solution = last.general_solution()?;
+				((0..solution.particular.len()).collect(), solution)
+			}
+			RightSides::Independent => {
+				let mut right_sides = Vec::new();
+				let mut solution: Option<GeneralSolution<T>> = None;
+				for (c, outcome) in last.outcomes()?.into_iter().enumerate() {
+					if let Some(s) = outcome.general_solution() {
+						right_sides.push(c);
+						match &mut solution {
+							Some(solution) => solution.particular.extend(s.particular),
+							None => solution = Some(s),
+						}
+					}
+				}
+				(right_sides, solution?)
+			}
+		};
+		Some(Residuals::new(
+			&self[0],
+			right_sides,
+			&solution.particular,
+			&solution.null_space,
+		))
+	}
+
+	/// How the right sides are reported, see [`RightSides`].
+	pub fn with_right_sides(mut self, right_sides: RightSides) -> Self {
+		self.right_sides = right_sides;
+		self
+	}
+
+	pub fn right_sides(&self) -> RightSides {
+		self.right_sides
 	}
 
-	pub fn solve(&mut self) {
+	/// Every variable as an expression of the parameters, `None` if there is no solution.
+	/// The right sides are [`RightSides::Parametric`] here, use [`Solution::from`] on
+	/// the [`Matrix::outcomes`] for independent ones.
+	pub fn solution(&self) -> Option<Solution<T>> {
+		self[-1].general_solution().map(Solution::from)
+	}
+
+	/// The name of `parameter` like it is displayed.
+	pub fn parameter_name(&self, p: Parameter) -> String {
+		match p {
+			Parameter::RightSide(i) => String::from(parameter(i)),
+			Parameter::FreeVariable(i) => self.variable_name(i),
+		}
+	}
+
+	/// One outcome per right side once solved, see [`Matrix::outcomes`].
+	pub fn outcomes(&self) -> Option<Vec<SolveOutcome<T>>> {
+		self[-1].outcomes()
+	}
+
+	/// Uses `names` instead of `x_1, x_2, ...` when displaying the solution,
+	/// e.g. the ones returned by [`parse_equations`](crate::parser::parse_equations).
+	pub fn with_variable_names(mut self, names: Vec<String>) -> Self {
+		self.variable_names = names;
+		self
+	}
+
+	/// The name of the variable of column `index`.
+	pub fn variable_name(&self, index: usize) -> String {
+		self.variable_names.get(index).cloned().unwrap_or_else(|| {
+			format!(
+				"x_{:0>width$}",
+				index + 1,
+				width = self[0].col_sequence().len().to_string().len()
+			)
+		})
+	}
+
+	/// Calculates all remaining steps, see [`Matrix::outcome`].
+	pub fn solve(&mut self) -> Option<SolveOutcome<T>> {
 		while let Some(new_matrix) = self.matrices.last().and_then(Matrix::calculate_next) {
 			self.matrices.push(new_matrix);
 		}
+		debug_verify(&self[0], &self[-1]);
+		self[-1].outcome()
+	}
+
+	/// Like [`MatrixSolver::solve`], but stops at the first step that overflows.
+	/// The history keeps all steps calculated before the overflow.
+	pub fn try_solve(&mut self) -> Result<Option<SolveOutcome<T>>, ArithmeticOverflow> {
+		loop {
+			let last = &self[-1];
+			match last.try_calculate_next() {
+				Ok(Some(new_matrix)) => self.matrices.push(new_matrix),
+				Ok(None) => {
+					debug_verify(&self[0], last);
+					return Ok(last.outcome());
+				}
+				Err(cell) => {
+					return Err(ArithmeticOverflow {
+						step: self.matrices.len(),
+						state: last.state().clone(),
+						cell,
+					})
+				}
+			}
+		}
 	}
 
-	pub fn get(mut self, index: isize) -> Matrix {
+	pub fn get(mut self, index: isize) -> Matrix<T> {
 		if index < 0 {
 			self.matrices
-				.swap_remove(self.matrices.len() - index.abs() as usize)
+				.swap_remove(self.matrices.len() - index.unsigned_abs())
 		} else {
 			self.matrices.swap_remove(index as usize)
 		}
 	}
+
+	/// The lines of the `align*` showing `outcome`.
+	fn latex_outcome(&self, outcome: Option<SolveOutcome<T>>) -> Vec<String> {
+		if let Some(SolveOutcome::Inconsistent { row }) = outcome {
+			return vec![format!(
+				"&\\text{{No solution, equation {} reduces to }} 0 = c \\neq 0",
+				row + 1
+			)];
+		}
+		let Some(solution) = outcome.and_then(SolveOutcome::general_solution) else {
+			return vec![String::from("&\\text{Failed to solve matrix.}")];
+		};
+		let last = &self[-1];
+		let rank = last.col_sequence().len() - solution.free_variables.len();
+		last.col_sequence()[..rank]
+			.iter()
+			.map(|v| {
+				let constants = solution
+					.particular
+					.iter()
+					.enumerate()
+					.map(|(i, p)| (p[*v].clone(), (i > 0).then(|| String::from(parameter(i)))));
+				let free = solution
+					.free_variables
+					.iter()
+					.zip(&solution.null_space)
+					.map(|(free, n)| (n[*v].clone(), Some(self.latex_variable_name(*free))));
+				format!(
+					"{} &= {}",
+					self.latex_variable_name(*v),
+					latex_sum(constants.chain(free))
+				)
+			})
+			.chain(
+				solution
+					.free_variables
+					.iter()
+					.map(|free| format!("{} &\\text{{ is free}}", self.latex_variable_name(*free))),
+			)
+			.collect()
+	}
+
+	/// Writes `outcome` like [`Display`] does.
+	fn fmt_outcome(
+		&self,
+		f: &mut std::fmt::Formatter,
+		outcome: Option<SolveOutcome<T>>,
+	) -> std::fmt::Result {
+		if let Some(SolveOutcome::Inconsistent { row }) = outcome {
+			return writeln!(
+				f,
+				"No solution, equation {} reduces to 0 = c with c ≠ 0.",
+				row + 1
+			);
+		}
+		match outcome.and_then(SolveOutcome::general_solution) {
+			Some(solution) => self.fmt_solution(f, &solution),
+			None => writeln!(f, "Failed to solve matrix."),
+		}
+	}
+
+	/// Writes one line per pivot variable followed by the free variables.
+	fn fmt_solution(
+		&self,
+		f: &mut std::fmt::Formatter,
+		solution: &GeneralSolution<T>,
+	) -> std::fmt::Result {
+		let last = &self[-1];
+		let names = (0..last.col_sequence().len())
+			.map(|i| self.variable_name(i))
+			.collect::<Vec<String>>();
+		let longest = names.iter().map(String::len).max().unwrap_or(0);
+		let rank = last.col_sequence().len() - solution.free_variables.len();
+		let parameters = solution.particular.len() + solution.free_variables.len();
+		let term = |f: &T, name: Option<String>| {
+			(
+				f.is_negative(),
+				if f.is_zero() && parameters > 1 {
+					String::new()
+				} else if let Some(name) = name {
+					if f.abs().is_one() {
+						name
+					} else {
+						format!("{}{}", f.abs(), name)
+					}
+				} else {
+					f.to_string()
+				},
+			)
+		};
+		let result = last.col_sequence()[..rank]
+			.iter()
+			.map(|v| {
+				solution
+					.particular
+					.iter()
+					.enumerate()
+					.map(|(i, p)| term(&p[*v], (i > 0).then(|| String::from(parameter(i)))))
+					.chain(
+						solution
+							.free_variables
+							.iter()
+							.zip(&solution.null_space)
+							.map(|(free, n)| term(&n[*v], Some(names[*free].clone()))),
+					)
+					.collect::<Vec<(bool, String)>>()
+			})
+			.collect::<Vec<Vec<(bool, String)>>>();
+		let max = (0..parameters)
+			.filter_map(|i| result.iter().map(|v| v[i].1.len()).max())
+			.collect::<Vec<usize>>();
+		for (i, r) in result.iter().enumerate() {
+			writeln!(
+				f,
+				"{:<longest$} = {}",
+				names[last.col_sequence()[i]],
+				r.iter()
+					.enumerate()
+					.map(|(i, s)| if i == 0 {
+						format!("{:>width$}", s.1, width = max[i])
+					} else if s.1.is_empty() {
+						" ".repeat(max[i] + 3)
+					} else {
+						format!(
+							" {} {:>width$}",
+							if s.0 { "-" } else { "+" },
+							s.1,
+							width = max[i]
+						)
+					})
+					.collect::<Vec<String>>()
+					.join("")
+			)?;
+		}
+		for free in &solution.free_variables {
+			writeln!(f, "{} is free", names[*free])?;
+		}
+		Ok(())
+	}
+
+	fn latex_variable_name(&self, index: usize) -> String {
+		self.variable_names
+			.get(index)
+			.cloned()
+			.unwrap_or_else(|| format!("x_{{{}}}", index + 1))
+	}
+
+	/// Every step in a LaTeX `gather*`, the [`MatrixState`] leading to a step is written above
+	/// the arrow in front of it. The solution follows in an `align*`.
+	pub fn to_latex(&self) -> String {
+		let mut latex = format!("\\begin{{gather*}}\n{}", self[0].to_latex());
+		for step in self.matrices.windows(2) {
+			latex += &format!(
+				" \\\\\n\\xrightarrow{{\\text{{{}}}}} {}",
+				step[0].state(),
+				step[1].to_latex()
+			);
+		}
+		let last = &self[-1];
+		let lines = match (self.right_sides, last.outcomes()) {
+			(RightSides::Independent, Some(outcomes)) => outcomes
+				.into_iter()
+				.enumerate()
+				.flat_map(|(i, outcome)| {
+					let mut lines = vec![format!("&\\text{{Right side {}:}}", i + 1)];
+					lines.append(&mut self.latex_outcome(Some(outcome)));
+					lines
+				})
+				.collect(),
+			_ => self.latex_outcome(last.outcome()),
+		};
+		latex.push_str(&format!(
+			"\n\\end{{gather*}}\n\\begin{{align*}}\n{}\n\\end{{align*}}",
+			lines.join(" \\\\\n")
+		));
+		latex
+	}
 }
-impl Index<isize> for MatrixSolver {
-	type Output = Matrix;
+/// Calculates the steps of a matrix lazily without keeping the history.
+///
+/// Not an [`Iterator`] since every step borrows the single matrix which is mutated in place.
+pub struct Steps<T = Fraction> {
+	matrix: Matrix<T>,
+	step: usize,
+}
+impl<T: Scalar> Steps<T> {
+	pub fn new(initial: Matrix<T>) -> Self {
+		Self {
+			matrix: initial,
+			step: 0,
+		}
+	}
+
+	/// The initial matrix first, then every calculated step together with the
+	/// [`MatrixState`] which led to it. `None` once the matrix is done.
+	pub fn try_next_step(
+		&mut self,
+	) -> Result<Option<(MatrixState, &Matrix<T>)>, ArithmeticOverflow> {
+		if self.step == 0 {
+			self.step = 1;
+			return Ok(Some((MatrixState::Initial, &self.matrix)));
+		}
+		let state = self.matrix.state().clone();
+		match self.matrix.try_step() {
+			Ok(true) => {
+				self.step += 1;
+				Ok(Some((state, &self.matrix)))
+			}
+			Ok(false) => Ok(None),
+			Err(cell) => Err(ArithmeticOverflow {
+				step: self.step,
+				state,
+				cell,
+			}),
+		}
+	}
+
+	/// Like [`Steps::try_next_step`], but panics if a cell overflows.
+	pub fn next_step(&mut self) -> Option<(MatrixState, &Matrix<T>)> {
+		self.try_next_step().unwrap_or_else(|e| panic!("{e}"))
+	}
+
+	/// The current matrix, which is the last step returned.
+	pub fn into_matrix(self) -> Matrix<T> {
+		self.matrix
+	}
+}
+/// Checks the solution of `solved` against `initial` in the crate's own tests if `T` has no
+/// rounding errors. Solutions whose substitution overflows are skipped.
+pub(crate) fn debug_verify<T: Scalar>(initial: &Matrix<T>, solved: &Matrix<T>) {
+	if !cfg!(test) || !T::EXACT || initial.tolerance() != 0.0 {
+		return;
+	}
+	if let Some(residuals) = solved.general_solution().and_then(|solution| {
+		Residuals::try_new(
+			initial,
+			(0..solution.particular.len()).collect(),
+			&solution.particular,
+			&solution.null_space,
+		)
+	}) {
+		debug_assert!(
+			residuals.is_exact(),
+			"The solution doesn't solve the initial matrix: {residuals:?}"
+		);
+	}
+}
+/// The name of the `i`th right side's parameter, starting with `t` for the second one.
+fn parameter(i: usize) -> char {
+	(b't' + (i % u8::MAX as usize) as u8 - 1) as char
+}
+/// Sums up the non-zero `terms`, each being a coefficient and an optional variable.
+fn latex_sum<T: Scalar>(terms: impl Iterator<Item = (T, Option<String>)>) -> String {
+	let mut sum = String::new();
+	for (f, name) in terms.filter(|(f, _)| !f.is_zero()) {
+		let value = match name {
+			Some(name) if f.abs().is_one() => name,
+			Some(name) => format!("{}{}", f.abs().to_latex(), name),
+			None => f.abs().to_latex(),
+		};
+		sum += &match (sum.is_empty(), f.is_negative()) {
+			(true, false) => value,
+			(true, true) => format!("-{value}"),
+			(false, false) => format!(" + {value}"),
+			(false, true) => format!(" - {value}"),
+		};
+	}
+	if sum.is_empty() {
+		String::from("0")
+	} else {
+		sum
+	}
+}
+impl<T: Scalar> Index<isize> for MatrixSolver<T> {
+	type Output = Matrix<T>;
 
 	fn index(&self, index: isize) -> &Self::Output {
 		if index < 0 {
-			&self.matrices[self.matrices.len() - index.abs() as usize]
+			&self.matrices[self.matrices.len() - index.unsigned_abs()]
 		} else {
 			&self.matrices[index as usize]
 		}
 	}
 }
-impl Display for MatrixSolver {
+/// Prints every step followed by the solution, `{:#}` only prints the solution.
+impl<T: Scalar> Display for MatrixSolver<T> {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-		writeln!(f, "Initial matrix:")?;
-		for m in &self.matrices {
-			writeln!(f, "{}\n\n{}:", m, m.state())?;
+		if !f.alternate() {
+			writeln!(f, "Initial matrix:")?;
+			for m in &self.matrices {
+				for operation in m.operations() {
+					writeln!(f, "{}", operation)?;
+				}
+				writeln!(f, "{}\n\n{}:", m, m.state())?;
+			}
 		}
 		let last = &self[-1];
-		if last.state() == &MatrixState::Done {
-			let result = last
-				.row_sequence()
-				.iter()
-				.map(|i| {
-					let r = &last.rows()[*i];
-					r.right()
-						.iter()
-						.enumerate()
-						.map(|(i, f)| {
-							(
-								f.is_negative(),
-								if f.sign() == 0 && r.right().len() > 1 {
-									String::new()
-								} else if i > 0 && f.as_f64().abs() == 1.0 {
-									String::from(
-										(b't' + (i % u8::MAX as usize) as u8 - 1) as char,
-									)
-								} else if i == 0 {
-									f.to_string()
-								} else {
-									format!(
-										"{}{}",
-										f.abs(),
-										(b't' + (i % u8::MAX as usize) as u8 - 1) as char
-									)
-								},
-							)
-						})
-						.collect::<Vec<(bool, String)>>()
-				})
-				.collect::<Vec<Vec<(bool, String)>>>();
-			let max = (0..result[0].len())
-				.filter_map(|i| result.iter().map(|v| v[i].1.len()).max())
-				.collect::<Vec<usize>>();
-			for (i, r) in result.iter().enumerate() {
-				writeln!(
-					f,
-					"x_{:0>width$} = {}",
-					last.col_sequence()[i] + 1,
-					r.iter()
-						.enumerate()
-						.map(|(i, s)| if i == 0 {
-							format!("{:>width$}", s.1, width = max[i])
-						} else if s.1.is_empty() {
-							" ".repeat(max[i] + 3)
-						} else {
-							format!(
-								" {} {:>width$}",
-								if s.0 { "-" } else { "+" },
-								s.1,
-								width = max[i]
-							)
-						})
-						.collect::<Vec<String>>()
-						.join(""),
-					width = result.len().to_string().len()
-				)?;
+		match (self.right_sides, last.outcomes()) {
+			(RightSides::Independent, Some(outcomes)) => {
+				for (i, outcome) in outcomes.into_iter().enumerate() {
+					if i > 0 {
+						writeln!(f)?;
+					}
+					writeln!(f, "Right side {}:", i + 1)?;
+					self.fmt_outcome(f, Some(outcome))?;
+				}
+				Ok(())
 			}
-		} else {
-			writeln!(f, "Failed to solve matrix.")?;
+			_ => self.fmt_outcome(f, last.outcome()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		fraction::Fraction,
+		matrix,
+		matrix::{MatrixState, PivotStrategy, RowOperation},
+		solution::{Residuals, SolveOutcome},
+		solve_in_place, solve_with_history,
+		solver::{MatrixSolver, RightSides, Steps},
+		steps, try_solve,
+	};
+
+	#[test]
+	fn operations() {
+		let solver = solve_with_history(matrix![[2; 1] | [3], [1; 3] | [4]]);
+		assert_eq!(solver[0].operations(), &vec![]);
+		assert_eq!(
+			solver[1].operations(),
+			&vec![RowOperation::FractionFreeCombine {
+				target: 1,
+				target_factor: Fraction::from(2),
+				source: 0,
+				source_factor: Fraction::ONE
+			}]
+		);
+		assert_eq!(
+			solver[2].operations(),
+			&vec![RowOperation::Scale {
+				row: 1,
+				factor: Fraction::positive(1, 5)
+			}]
+		);
+		assert_eq!(solver[1].operations()[0].to_string(), "R2 ← 2·R2 − 1·R1");
+
+		// the second row is already zero below the first pivot, so it is only scaled
+		let solver = solve_with_history(
+			matrix![[2; 1] | [3], [0; 1] | [1], [1; 3] | [4]]
+				.with_pivot_strategy(PivotStrategy::Natural),
+		);
+		assert_eq!(
+			solver[1].operations(),
+			&vec![
+				RowOperation::Scale {
+					row: 1,
+					factor: Fraction::from(2)
+				},
+				RowOperation::FractionFreeCombine {
+					target: 2,
+					target_factor: Fraction::from(2),
+					source: 0,
+					source_factor: Fraction::ONE
+				}
+			]
+		);
+		// the first row is already zero in the col of the second pivot
+		let solver = solve_with_history(
+			matrix![[1; 0; 1] | [2], [0; 1; 1] | [2], [0; 0; 1] | [1]]
+				.with_pivot_strategy(PivotStrategy::Natural),
+		);
+		assert_eq!(solver[1].state(), &MatrixState::ReInsertRow(0));
+		assert_eq!(
+			solver[2].operations(),
+			&vec![RowOperation::FractionFreeCombine {
+				target: 0,
+				target_factor: Fraction::ONE,
+				source: 2,
+				source_factor: Fraction::ONE
+			}]
+		);
+		assert_eq!(
+			RowOperation::AddMultiple {
+				target: 0,
+				source: 2,
+				factor: Fraction::negative(1, 2)
+			}
+			.to_string(),
+			"R1 ← R1 − 1/2·R3"
+		);
+		assert_eq!(
+			RowOperation::FractionFreeCombine {
+				target: 0,
+				target_factor: Fraction::ONE,
+				source: 1,
+				source_factor: Fraction::M_ONE
+			}
+			.to_string(),
+			"R1 ← 1·R1 + 1·R2"
+		);
+		assert_eq!(
+			RowOperation::<Fraction>::Swap { a: 0, b: 1 }.to_string(),
+			"R1 ↔ R2"
+		);
+	}
+
+	#[test]
+	fn in_place() {
+		let initial = matrix![[2; 1] | [3], [1; 3] | [4]];
+		let history = solve_with_history(initial.clone());
+		let mut steps = steps(initial.clone());
+		let mut i = 0;
+		while let Some((state, m)) = steps.next_step() {
+			if i == 0 {
+				assert_eq!(state, MatrixState::Initial);
+			} else {
+				assert_eq!(&state, history[i as isize - 1].state());
+			}
+			assert_eq!(m, &history[i as isize]);
+			i += 1;
+		}
+		assert_eq!(steps.into_matrix(), history[-1]);
+
+		let mut cut = initial;
+		assert_eq!(
+			solve_in_place(&mut cut),
+			Ok(Some(SolveOutcome::Unique {
+				solution: vec![vec![Fraction::ONE, Fraction::ONE]]
+			}))
+		);
+		assert_eq!(cut, history[-1]);
+
+		let mut cut = matrix![[2; 1] | [1], [1; usize::MAX] | [1]];
+		let initial = cut.clone();
+		assert_eq!(solve_in_place(&mut cut).unwrap_err().step, 1);
+		assert_eq!(cut, initial);
+		let mut steps = Steps::new(initial);
+		assert!(steps.try_next_step().is_ok());
+		assert!(steps.try_next_step().is_err());
+	}
+
+	#[test]
+	fn independent_right_sides() {
+		let mut solver = MatrixSolver::new(matrix![[1; 1] | [2; 0], [1; -1] | [0; 2]])
+			.with_right_sides(RightSides::Independent);
+		solver.solve();
+		assert_eq!(
+			solver.outcomes(),
+			Some(vec![
+				SolveOutcome::Unique {
+					solution: vec![vec![Fraction::ONE, Fraction::ONE]]
+				},
+				SolveOutcome::Unique {
+					solution: vec![vec![Fraction::ONE, Fraction::M_ONE]]
+				}
+			])
+		);
+		assert_eq!(
+			format!("{solver:#}"),
+			"Right side 1:\nx_1 = 1\nx_2 = 1\n\nRight side 2:\nx_1 =  1\nx_2 = -1\n"
+		);
+
+		let mut solver = MatrixSolver::new(matrix![[1; 1] | [2; 1], [2; 2] | [4; 3]])
+			.with_right_sides(RightSides::Independent);
+		assert_eq!(solver.solve(), Some(SolveOutcome::Inconsistent { row: 1 }));
+		let outcomes = solver.outcomes().unwrap();
+		assert!(matches!(outcomes[0], SolveOutcome::Infinite { .. }));
+		assert_eq!(outcomes[1], SolveOutcome::Inconsistent { row: 1 });
+		assert_eq!(
+			format!("{solver:#}"),
+			"Right side 1:\nx_1 = 2 - x_2\nx_2 is free\n\nRight side 2:\nNo solution, equation 2 reduces to 0 = c with c ≠ 0.\n"
+		);
+		assert!(solver.to_latex().ends_with(
+			"&\\text{Right side 2:} \\\\\n&\\text{No solution, equation 2 reduces to } 0 = c \\neq 0\n\\end{align*}"
+		));
+	}
+
+	#[test]
+	fn verify() {
+		let mut solver = MatrixSolver::new(matrix![[1; 2; 1] | [(1, 2); 1], [2; 4; 0] | [0; 0]]);
+		assert_eq!(solver.verify(), None);
+		solver.solve();
+		let residuals = solver.verify().unwrap();
+		assert_eq!(residuals.right_sides, vec![0, 1]);
+		assert_eq!(residuals.rows, vec![vec![Fraction::ZERO; 2]; 2]);
+		assert_eq!(residuals.null_space, vec![vec![Fraction::ZERO]; 2]);
+		assert!(residuals.is_exact());
+
+		let mut solver = MatrixSolver::new(matrix![[1; 1] | [2; 1], [2; 2] | [4; 3]])
+			.with_right_sides(RightSides::Independent);
+		solver.solve();
+		assert_eq!(solver.verify().unwrap().right_sides, vec![0]);
+		assert_eq!(
+			solver.with_right_sides(RightSides::Parametric).verify(),
+			None
+		);
+
+		// x = 1, y = 1 solves the first equation only
+		let residuals = Residuals::new(
+			&matrix![[1; 1] | [2], [1; -1] | [1]],
+			vec![0],
+			&[vec![Fraction::ONE, Fraction::ONE]],
+			&[],
+		);
+		assert_eq!(
+			residuals.rows,
+			vec![vec![Fraction::ZERO], vec![Fraction::M_ONE]]
+		);
+
+		// solving works, but substituting the solution overflows
+		let m = 1usize << 63;
+		let cut = matrix![[m; m; 1] | [m], [0; 1; 0] | [1], [1; 0; 0] | [1]];
+		let solution = try_solve(cut.clone()).unwrap().general_solution().unwrap();
+		assert_eq!(
+			Residuals::try_new(&cut, vec![0], &solution.particular, &solution.null_space),
+			None
+		);
+		assert_eq!(residuals.wrong_rows(0.0), vec![1]);
+		assert!(residuals.is_within(1.0) && !residuals.is_exact());
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn serde() {
+		use crate::matrix::Matrix;
+
+		let mut solver = MatrixSolver::new(
+			matrix![[1; 1; 1] | [6], [1; -1; 0] | [-1], [0; 2; 1] | [7]]
+				.with_pivot_strategy(PivotStrategy::Markowitz),
+		);
+		let outcome = solver.solve();
+		let json = serde_json::to_string(&solver).unwrap();
+		let copy = serde_json::from_str::<MatrixSolver>(&json).unwrap();
+		assert_eq!(copy.to_string(), solver.to_string());
+		for i in 0..3 {
+			assert_eq!(copy[i].state(), solver[i].state());
+			assert_eq!(copy[i].operations(), solver[i].operations());
+		}
+		// an unfinished matrix continues where it stopped
+		let mut m =
+			serde_json::from_str::<Matrix>(&serde_json::to_string(&solver[1]).unwrap()).unwrap();
+		while m.try_step().unwrap() {}
+		assert_eq!(m.general_solution().map(SolveOutcome::from), outcome);
+		assert_eq!(
+			serde_json::to_string(&MatrixState::NormalizeRow(2)).unwrap(),
+			r#"{"NormalizeRow":2}"#
+		);
+		let solution = solver.solution().unwrap();
+		assert_eq!(
+			serde_json::from_str::<crate::solution::Solution>(
+				&serde_json::to_string(&solution).unwrap()
+			)
+			.unwrap(),
+			solution
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn serde_validation() {
+		use crate::solver::InvalidHistory;
+
+		let one = r#"[{"left":["1"],"right":["1"]}]"#;
+		let wide = r#"[{"left":["1","2"],"right":["1"]}]"#;
+		let history = |matrices: &str| {
+			serde_json::from_str::<MatrixSolver>(&format!(
+				r#"{{"matrices":{matrices},"variable_names":[],"right_sides":"Parametric"}}"#
+			))
+		};
+		let step = |rows: &str, col_sequence: &str| {
+			format!(
+				r#"{{"rows":{rows},"state":"Done","row_sequence":[0],"col_sequence":{col_sequence},"pivot_strategy":"Natural","tolerance":0.0,"operations":[]}}"#
+			)
+		};
+		let rejected = |h: Result<MatrixSolver, serde_json::Error>, e: InvalidHistory| {
+			h.is_err_and(|h| h.to_string().starts_with(&e.to_string()))
+		};
+		assert!(rejected(history("[]"), InvalidHistory::Empty));
+		assert!(history(&format!("[{},{}]", step(one, "[0]"), step(one, "[0]"))).is_ok());
+		assert!(rejected(
+			history(&format!("[{},{}]", step(one, "[0]"), step(wide, "[0,1]"))),
+			InvalidHistory::Shape(1)
+		));
+
+		// every step of every strategy passes the checks
+		let systems = [
+			matrix![[0; 1; 2] | [1], [0; 2; 4] | [2], [1; 0; 0] | [0]],
+			matrix![[1; 0] | [1], [0; 1] | [1], [1; 1] | [2]],
+			matrix![[0; 2; 1; 1] | [1; 0], [1; 1; 0; 3] | [1; 1]],
+		];
+		for (m, strategy) in systems.iter().flat_map(|m| {
+			[
+				PivotStrategy::Natural,
+				PivotStrategy::MaxZeros,
+				PivotStrategy::GreedyMaxZeros,
+				PivotStrategy::SmallestEntry,
+				PivotStrategy::Markowitz,
+				PivotStrategy::LargestMagnitude,
+			]
+			.map(|strategy| (m, strategy))
+		}) {
+			let mut solver = MatrixSolver::new(m.clone().with_pivot_strategy(strategy));
+			solver.solve();
+			let json = serde_json::to_string(&solver).unwrap();
+			assert_eq!(
+				serde_json::from_str::<MatrixSolver>(&json)
+					.unwrap()
+					.to_string(),
+				solver.to_string(),
+				"{strategy:?}"
+			);
 		}
-		Ok(())
 	}
 }